@@ -1,8 +1,16 @@
 mod a {
-    use arena_system::Arena;
+    use arena_system::{arena_world, World};
+
+    arena_world! {
+        pub struct Tests {
+            pub tests: b::Test<42, u32>,
+            pub other_tests: b::Test<24, u32>,
+        }
+    }
 
     pub mod b {
         #[derive(arena_system_proc_macro::Handleable, Debug)]
+        #[handleable(world = super::Tests)]
         pub struct Test<const TEST: usize, T: Default>
         where
             T: Clone + Copy,
@@ -12,19 +20,19 @@ mod a {
             pub test_t: T,
             #[handle_getter(return_type(copy))]
             pub test_i32: i32,
-            #[handle_getter(return_type(handle(tests: Arena<Test<24, u32>>)))]
+            #[handle_getter(return_type(handle(Test<24, u32>)))]
             pub test_index: usize,
         }
     }
 
     pub fn test() {
         use b::*;
-        use arena_system::Handle;
 
-        let mut test_arena: Arena<Test<42, u32>> = Arena::new();
-        test_arena.add(Test { test_t: 1, test_i32: 42, test_index: 0 });
+        let mut tests = Tests::new();
+        tests.add(Test::<42, u32> { test_t: 1, test_i32: 42, test_index: 0 });
+        tests.add(Test::<24, u32> { test_t: 24, test_i32: 0, test_index: 0 });
 
-        let test_handle = test_arena.handle(0i64.into(), None);
+        let test_handle = tests.handle::<Test<42, u32>>(0i64.into());
 
         println!("Test: {:?}", test_handle.test().unwrap());
         test_handle.set_test(100);
@@ -34,7 +42,7 @@ mod a {
         test_handle.set_test_i32(-100);
         println!("Test i32: {:?}", test_handle.test_i32().unwrap());
 
-        println!("Test index: {:?}", test_handle.test_index().unwrap().get());
+        println!("Test index: {:?}", test_handle.test_index().unwrap().test());
     }
 }
 
@@ -0,0 +1,311 @@
+use crate::handleable::HandleableInfo;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parenthesized, parse::Result, Attribute, Error, Expr, Field, Ident, Meta, Type, Visibility,
+};
+
+pub enum IndexSource {
+    Field,
+    Key(Expr),
+}
+
+pub struct IndexedField {
+    pub ident: Ident,
+    pub ty: Type,
+    pub unique: bool,
+    pub ordered: bool,
+    pub source: IndexSource,
+}
+
+impl IndexedField {
+    fn parse_field(f: &Field, a: &Attribute) -> Result<Self> {
+        let mut index = IndexedField {
+            ident: f.ident.clone().expect("Structs with unnamed fields are not supported"),
+            ty: f.ty.clone(),
+            unique: false,
+            ordered: false,
+            source: IndexSource::Field,
+        };
+
+        if !matches!(a.meta, Meta::Path(_)) {
+            a.parse_nested_meta(|meta| {
+                if meta.path.is_ident("unique") {
+                    index.unique = true;
+
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("ordered") {
+                    index.ordered = true;
+
+                    return Ok(());
+                }
+
+                Err(meta.error("unrecognised index attribute"))
+            })?;
+        }
+
+        Ok(index)
+    }
+
+    fn parse_key(a: &Attribute) -> Result<Self> {
+        let mut ident = None;
+        let mut ty = None;
+        let mut key = None;
+        let mut ordered = false;
+
+        a.parse_nested_meta(|meta| {
+            if meta.path.is_ident("ordered") {
+                ordered = true;
+
+                return Ok(());
+            }
+
+            let content;
+            parenthesized!(content in meta.input);
+
+            if meta.path.is_ident("name") {
+                ident = Some(content.parse::<Ident>()?);
+            } else if meta.path.is_ident("key") {
+                key = Some(content.parse::<Expr>()?);
+            } else if meta.path.is_ident("key_type") {
+                ty = Some(content.parse::<Type>()?);
+            } else {
+                return Err(meta.error("unrecognised key index attribute"));
+            }
+
+            Ok(())
+        })?;
+
+        let missing = |what| Error::new_spanned(a, format!("key indexes require `{what}(...)`"));
+
+        Ok(IndexedField {
+            ident: ident.ok_or_else(|| missing("name"))?,
+            ty: ty.ok_or_else(|| missing("key_type"))?,
+            unique: false,
+            ordered,
+            source: IndexSource::Key(key.ok_or_else(|| missing("key"))?),
+        })
+    }
+
+    fn index_ty(&self) -> TokenStream {
+        let ty = &self.ty;
+
+        match self.ordered {
+            true => quote!(arena_system::BTreeIndex<#ty>),
+            false => quote!(arena_system::HashIndex<#ty>),
+        }
+    }
+
+    fn key(&self, element: &Ident) -> TokenStream {
+        let ident = &self.ident;
+
+        match &self.source {
+            IndexSource::Field => quote!(&#element.#ident),
+            IndexSource::Key(key) => quote!(&(#key)(#element)),
+        }
+    }
+
+    fn lookups(&self) -> (TokenStream, TokenStream) {
+        let IndexedField { ident, ty, .. } = self;
+        let element = format_ident!("element");
+        let key = self.key(&element);
+
+        let find = format_ident!("find_by_{}", ident);
+        let find_all = format_ident!("find_all_by_{}", ident);
+        let mut decls = quote! {
+            fn #find(&self, value: &#ty) -> Option<arena_system::Index>;
+            fn #find_all(&self, value: &#ty) -> Vec<arena_system::Index>;
+        };
+        let mut impls = quote! {
+            fn #find(&self, value: &#ty) -> Option<arena_system::Index> {
+                self.with_indexes(|indexes| indexes.#ident.find(value))
+                    .unwrap_or_else(|| self.query().find(|#element| *#key == *value))
+            }
+
+            fn #find_all(&self, value: &#ty) -> Vec<arena_system::Index> {
+                self.with_indexes(|indexes| indexes.#ident.find_all(value).collect())
+                    .unwrap_or_else(|| {
+                        self.query().filter(|#element| *#key == *value).indices().collect()
+                    })
+            }
+        };
+
+        if self.ordered {
+            let range = format_ident!("range_by_{}", ident);
+            let ordered = format_ident!("ordered_by_{}", ident);
+
+            decls.extend(quote! {
+                fn #range(
+                    &self,
+                    range: impl std::ops::RangeBounds<#ty>,
+                ) -> Vec<arena_system::Index>;
+                fn #ordered(&self) -> Vec<arena_system::Index>;
+            });
+            impls.extend(quote! {
+                fn #range(
+                    &self,
+                    range: impl std::ops::RangeBounds<#ty>,
+                ) -> Vec<arena_system::Index> {
+                    match self.with_indexes(|indexes| {
+                        let bounds = (range.start_bound(), range.end_bound());
+
+                        indexes.#ident.range(bounds).collect()
+                    }) {
+                        Some(indices) => indices,
+                        None => self.range_by(|#element| Clone::clone(#key), range),
+                    }
+                }
+
+                fn #ordered(&self) -> Vec<arena_system::Index> {
+                    self.#range(..)
+                }
+            });
+        }
+
+        (decls, impls)
+    }
+}
+
+pub struct IndexesInfo<'a> {
+    pub handleable: &'a HandleableInfo,
+
+    pub vis: &'a Visibility,
+    pub indexes_ident: Ident,
+    pub lookup_ident: Ident,
+    pub fields: Vec<IndexedField>,
+}
+
+impl<'a> IndexesInfo<'a> {
+    pub fn parse(handleable_info: &'a HandleableInfo) -> Result<Option<Self>> {
+        let mut fields = vec![];
+        handleable_info.fields.iter().try_for_each(|f| {
+            f.attrs
+                .iter()
+                .filter(|a| a.path().is_ident("handle_index"))
+                .try_for_each(|a| {
+                    fields.push(IndexedField::parse_field(f, a)?);
+
+                    Ok::<_, Error>(())
+                })
+        })?;
+        handleable_info.key_indexes.iter().try_for_each(|a| {
+            fields.push(IndexedField::parse_key(a)?);
+
+            Ok::<_, Error>(())
+        })?;
+
+        if fields.is_empty() {
+            return Ok(None);
+        }
+
+        if !handleable_info.shared_generics.params.is_empty() {
+            return Err(Error::new_spanned(
+                &handleable_info.shared_generics,
+                "`#[handle_index]` is not supported on generic structs",
+            ));
+        }
+
+        let ident = &handleable_info.ident;
+
+        Ok(Some(Self {
+            handleable: handleable_info,
+            vis: &handleable_info.vis,
+            indexes_ident: format_ident!("{}Indexes", ident),
+            lookup_ident: format_ident!("{}Lookup", ident),
+            fields,
+        }))
+    }
+
+    pub fn quote(self) -> TokenStream {
+        let IndexesInfo { handleable, vis, indexes_ident, lookup_ident, fields } = &self;
+
+        let element_ty = &handleable.ident;
+        let element = format_ident!("element");
+        let idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+        let index_tys = fields.iter().map(IndexedField::index_ty).collect::<Vec<_>>();
+        let uniques = fields.iter().map(|f| f.unique);
+        let keys = fields.iter().map(|f| f.key(&element)).collect::<Vec<_>>();
+
+        let key_fields = fields.iter().filter(|f| matches!(f.source, IndexSource::Key(_)));
+        let key_idents = key_fields.clone().map(|f| &f.ident).collect::<Vec<_>>();
+        let key_keys = key_fields.map(|f| f.key(&element)).collect::<Vec<_>>();
+
+        let (lookup_decls, lookup_impls): (Vec<_>, Vec<_>) =
+            fields.iter().map(IndexedField::lookups).unzip();
+
+        quote! {
+            #[derive(Clone)]
+            #vis struct #indexes_ident {
+                #( #idents: #index_tys, )*
+            }
+
+            impl #indexes_ident {
+                #[allow(dead_code)]
+                fn insert_keys(&mut self, index: arena_system::Index, #element: &#element_ty) {
+                    #( self.#key_idents.insert(index, #key_keys); )*
+                }
+
+                #[allow(dead_code)]
+                fn remove_keys(&mut self, index: arena_system::Index, #element: &#element_ty) {
+                    #( self.#key_idents.remove(index, #key_keys); )*
+                }
+            }
+
+            impl Default for #indexes_ident {
+                fn default() -> Self {
+                    Self {
+                        #( #idents: <#index_tys>::new(stringify!(#idents), #uniques), )*
+                    }
+                }
+            }
+
+            impl arena_system::ElementIndexes<#element_ty> for #indexes_ident {
+                fn insert(
+                    &mut self,
+                    index: arena_system::Index,
+                    #element: &#element_ty,
+                ) -> arena_system::ArenaResult<()> {
+                    #( self.#idents.check(index, #keys)?; )*
+                    #( self.#idents.insert(index, #keys); )*
+
+                    Ok(())
+                }
+
+                fn remove(&mut self, index: arena_system::Index, #element: &#element_ty) {
+                    #( self.#idents.remove(index, #keys); )*
+                }
+
+                fn clone_box(&self) -> Box<dyn arena_system::ElementIndexes<#element_ty>> {
+                    Box::new(self.clone())
+                }
+
+                fn as_any(&self) -> &dyn std::any::Any {
+                    self
+                }
+
+                fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+                    self
+                }
+            }
+
+            impl arena_system::Indexed for #element_ty {
+                type Indexes = #indexes_ident;
+            }
+
+            #[allow(clippy::ptr_arg)]
+            #vis trait #lookup_ident {
+                #( #lookup_decls )*
+            }
+
+            #[allow(clippy::ptr_arg)]
+            impl<S: ?Sized + arena_system::Storage<#element_ty>> #lookup_ident
+                for arena_system::Arena<#element_ty, S>
+            {
+                #( #lookup_impls )*
+            }
+        }
+    }
+}
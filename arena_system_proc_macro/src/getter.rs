@@ -1,39 +1,38 @@
-use crate::util::{parse_name_attr, parse_vis_attr, unwrap_type};
+use crate::util::{parse_name_attr, parse_vis_attr, HandleKind};
 
 use proc_macro2::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{
-    parenthesized, parse::Result, parse_quote, spanned::Spanned, Field, Ident, Lifetime, Type,
-    Visibility, Token,
+    parenthesized, parse::Result, parse_quote, spanned::Spanned, Error, Field, Ident, Lifetime,
+    Type, Visibility,
 };
 
+enum ReturnType {
+    Reference,
+    Clone,
+    Copy,
+    Handle { element: Box<Type>, lifetime: Lifetime },
+}
+
 pub struct Getter {
     pub vis: Visibility,
     pub ident: Ident,
+    pub receiver: TokenStream,
     pub return_ty: Type,
     pub body: TokenStream,
 }
 
 impl Getter {
-    pub fn new(f: &Field, lifetime: &Lifetime) -> Result<Getter> {
+    pub fn new(f: &Field, kind: &HandleKind) -> Result<Getter> {
         let field_ident = f.ident.clone().expect("Structs with unnamed fields are not supported");
         let field_ty = &f.ty;
         let field_ty_span = field_ty.span();
 
-        let mut return_ty: Type =
-            parse_quote!(Option<arena_system::ElementRef<#lifetime, #field_ty>>);
-        let mut fn_body = quote_spanned! { field_ty_span =>
-            use arena_system::Handle;
-            self.get()
-                .ok()
-                .map(|this_ref| arena_system::ElementRef::map(
-                    this_ref,
-                    |this| {
-                        &this.#field_ident
-                    }
-                ))
-        };
+        let handle_trait = kind.handle_trait();
+        let ref_ty = kind.ref_type(field_ty);
+        let map_ref = kind.map_ref();
 
+        let mut return_type = ReturnType::Reference;
         let mut fn_ident = field_ident.clone();
         let mut fn_vis = f.vis.clone();
 
@@ -55,76 +54,34 @@ impl Getter {
                     }
 
                     if meta.path.is_ident("return_type") {
-                        let return_type;
-                        parenthesized!(return_type in meta.input);
-
-                        let return_ident = return_type.parse::<Ident>()?;
-                        match return_ident.to_string().as_str() {
-                            "reference" => {
-                                return_ty = parse_quote!(
-                                    Option<arena_system::ElementRef<#lifetime, #field_ty>>
-                                );
-                                fn_body = quote_spanned! { field_ty_span =>
-                                    use arena_system::Handle;
-                                    self.get()
-                                        .ok()
-                                        .map(|this_ref| arena_system::ElementRef::map(
-                                            this_ref,
-                                            |this| &this.#field_ident,
-                                        ))
-                                };
-                            }
-                            "clone" => {
-                                return_ty = parse_quote!(Option<#field_ty>);
-                                fn_body = quote_spanned! { field_ty_span =>
-                                    fn _static_assert_clone<_StaticAssertClone: Clone>() {}
-                                    _static_assert_clone::<#field_ty>();
-
-                                    use arena_system::Handle;
-                                    self.get()
-                                        .ok()
-                                        .map(|this_ref| this_ref.#field_ident.clone())
-                                };
-                            }
-                            "copy" => {
-                                return_ty = parse_quote!(Option<#field_ty>);
-                                fn_body = quote_spanned! { field_ty_span =>
-                                    fn _static_assert_copy<_StaticAssertCopy: Copy>() {}
-                                    _static_assert_copy::<#field_ty>();
-
-                                    use arena_system::Handle;
-                                    self.get()
-                                        .ok()
-                                        .map(|this_ref| this_ref.#field_ident)
-                                };
-                            }
+                        let return_type_content;
+                        parenthesized!(return_type_content in meta.input);
+
+                        let return_ident = return_type_content.parse::<Ident>()?;
+                        return_type = match return_ident.to_string().as_str() {
+                            "reference" => ReturnType::Reference,
+                            "clone" => ReturnType::Clone,
+                            "copy" => ReturnType::Copy,
                             "handle" => {
-                                let arena;
-                                parenthesized!(arena in return_type);
-
-                                let arena_ident = arena.parse::<Ident>()?;
-                                arena.parse::<Token![:]>()?;
-                                let arena_type = arena.parse::<Type>()?;
-                                let element_type = unwrap_type("Arena", &arena_type)?;
-
-                                return_ty = parse_quote!(
-                                    Option<
-                                        <#element_type as arena_system::Handleable<'arena>>::Handle
-                                    >
-                                );
-                                fn_body = quote_spanned! { field_ty_span =>
-                                    use arena_system::Handle;
-
-                                    self.get()
-                                        .ok()
-                                        .map(|this_ref| {
-                                            self.#arena_ident
-                                                .handle(this_ref.#field_ident.into(), None)
-                                        })
+                                let HandleKind::Borrowed { lifetime, world: true, .. } = kind
+                                else {
+                                    return Err(Error::new_spanned(
+                                        return_ident,
+                                        "handle getters require `#[handleable(world = ...)]` \
+                                        and aren't supported by shared and soa handles",
+                                    ));
                                 };
+
+                                let element;
+                                parenthesized!(element in return_type_content);
+
+                                ReturnType::Handle {
+                                    element: Box::new(element.parse::<Type>()?),
+                                    lifetime: lifetime.clone(),
+                                }
                             }
                             _ => return Err(meta.error("unrecognised return type")),
-                        }
+                        };
 
                         return Ok(());
                     }
@@ -133,14 +90,182 @@ impl Getter {
                 })
             })?;
 
-        Ok(Getter { vis: fn_vis, ident: fn_ident, return_ty, body: fn_body })
+        if let (Some(inner_ty), HandleKind::Borrowed { lifetime, .. }) =
+            (kind.field_cell(field_ty), kind)
+        {
+            let (return_ty, body) = Self::field_cell(&field_ident, inner_ty, lifetime, return_type);
+
+            return Ok(Getter {
+                vis: fn_vis,
+                ident: fn_ident,
+                receiver: kind.receiver(),
+                return_ty,
+                body,
+            });
+        }
+
+        let (return_ty, fn_body): (Type, _) = match (return_type, kind) {
+            (ReturnType::Reference, HandleKind::Soa { .. }) => (
+                parse_quote!(Option<#ref_ty>),
+                quote_spanned! { field_ty_span =>
+                    self.__arena.#field_ident.get(self.__index).ok()
+                },
+            ),
+            (ReturnType::Reference, _) => (
+                parse_quote!(Option<#ref_ty>),
+                quote_spanned! { field_ty_span =>
+                    use #handle_trait;
+                    self.get()
+                        .ok()
+                        .map(|this_ref| #map_ref(
+                            this_ref,
+                            |this| &this.#field_ident,
+                        ))
+                },
+            ),
+            (ReturnType::Clone, HandleKind::Soa { .. }) => (
+                parse_quote!(Option<#field_ty>),
+                quote_spanned! { field_ty_span =>
+                    self.__arena.#field_ident
+                        .get(self.__index)
+                        .ok()
+                        .map(|field| <#field_ty as Clone>::clone(&field))
+                },
+            ),
+            (ReturnType::Clone, _) => (
+                parse_quote!(Option<#field_ty>),
+                quote_spanned! { field_ty_span =>
+                    fn _static_assert_clone<_StaticAssertClone: Clone>() {}
+                    _static_assert_clone::<#field_ty>();
+
+                    use #handle_trait;
+                    self.get()
+                        .ok()
+                        .map(|this_ref| this_ref.#field_ident.clone())
+                },
+            ),
+            (ReturnType::Copy, HandleKind::Soa { .. }) => (
+                parse_quote!(Option<#field_ty>),
+                quote_spanned! { field_ty_span =>
+                    fn _static_assert_copy<_StaticAssertCopy: Copy>() {}
+                    _static_assert_copy::<#field_ty>();
+
+                    self.__arena.#field_ident.get(self.__index).ok().map(|field| *field)
+                },
+            ),
+            (ReturnType::Copy, _) => (
+                parse_quote!(Option<#field_ty>),
+                quote_spanned! { field_ty_span =>
+                    fn _static_assert_copy<_StaticAssertCopy: Copy>() {}
+                    _static_assert_copy::<#field_ty>();
+
+                    use #handle_trait;
+                    self.get()
+                        .ok()
+                        .map(|this_ref| this_ref.#field_ident)
+                },
+            ),
+            (ReturnType::Handle { element, lifetime }, _) => (
+                parse_quote!(Option<<#element as arena_system::Handleable<#lifetime>>::Handle>),
+                quote_spanned! { field_ty_span =>
+                    use arena_system::Handle;
+
+                    self.get()
+                        .ok()
+                        .map(|this_ref| {
+                            #[allow(clippy::useless_conversion)]
+                            let index: arena_system::Index =
+                                this_ref.#field_ident.into();
+
+                            arena_system::World::handle::<#element>(
+                                self.__userdata,
+                                index,
+                            )
+                        })
+                },
+            ),
+        };
+
+        Ok(Getter {
+            vis: fn_vis,
+            ident: fn_ident,
+            receiver: kind.receiver(),
+            return_ty,
+            body: fn_body,
+        })
+    }
+
+    // Field cells are borrowed through a shared borrow of the element, so getters and setters of
+    // different cells don't conflict.
+    fn field_cell(
+        field_ident: &Ident,
+        inner_ty: &Type,
+        lifetime: &Lifetime,
+        return_type: ReturnType,
+    ) -> (Type, TokenStream) {
+        let inner_ty_span = inner_ty.span();
+
+        match return_type {
+            ReturnType::Reference => (
+                parse_quote!(Option<arena_system::FieldRef<#lifetime, #inner_ty>>),
+                quote_spanned! { inner_ty_span =>
+                    use arena_system::Handle;
+                    self.get().ok().and_then(|this_ref| {
+                        arena_system::FieldRef::from_element(arena_system::ElementRef::map(
+                            this_ref,
+                            |this| &this.#field_ident,
+                        ))
+                        .ok()
+                    })
+                },
+            ),
+            ReturnType::Clone => (
+                parse_quote!(Option<#inner_ty>),
+                quote_spanned! { inner_ty_span =>
+                    use arena_system::Handle;
+                    self.get().ok().and_then(|this_ref| {
+                        let field = this_ref.#field_ident.try_borrow().ok()?;
+
+                        Some(<#inner_ty as Clone>::clone(&field))
+                    })
+                },
+            ),
+            ReturnType::Copy => (
+                parse_quote!(Option<#inner_ty>),
+                quote_spanned! { inner_ty_span =>
+                    fn _static_assert_copy<_StaticAssertCopy: Copy>() {}
+                    _static_assert_copy::<#inner_ty>();
+
+                    use arena_system::Handle;
+                    self.get().ok().and_then(|this_ref| {
+                        let field = this_ref.#field_ident.try_borrow().ok()?;
+
+                        Some(*field)
+                    })
+                },
+            ),
+            ReturnType::Handle { element, lifetime } => (
+                parse_quote!(Option<<#element as arena_system::Handleable<#lifetime>>::Handle>),
+                quote_spanned! { inner_ty_span =>
+                    use arena_system::Handle;
+                    self.get().ok().and_then(|this_ref| {
+                        let field = this_ref.#field_ident.try_borrow().ok()?;
+
+                        #[allow(clippy::useless_conversion)]
+                        let index: arena_system::Index = (*field).into();
+
+                        Some(arena_system::World::handle::<#element>(self.__userdata, index))
+                    })
+                },
+            ),
+        }
     }
 
     pub fn quote(self) -> TokenStream {
-        let Getter { vis, ident, return_ty, body } = self;
+        let Getter { vis, ident, receiver, return_ty, body } = self;
 
         quote! {
-            #vis fn #ident(&'arena self) -> #return_ty {
+            #vis fn #ident(#receiver) -> #return_ty {
                 #body
             }
         }
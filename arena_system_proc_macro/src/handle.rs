@@ -1,9 +1,8 @@
 use crate::getter::Getter;
-use crate::setter::Setter;
 use crate::handleable::HandleableInfo;
-use crate::util::iter_generics;
-
-use std::collections::HashMap;
+use crate::relation::RelationGetter;
+use crate::setter::Setter;
+use crate::util::{iter_generics, HandleKind};
 
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -14,17 +13,22 @@ pub struct HandleInfo<'a> {
 
     pub vis: &'a Visibility,
     pub ident: &'a Ident,
-    #[allow(unused)]
-    pub userdata: Option<HashMap<Ident, Type>>,
+    pub userdata: Type,
 }
 
 impl<'a> HandleInfo<'a> {
     pub fn parse(handleable_info: &'a HandleableInfo) -> Self {
+        let lifetime = &handleable_info.lifetime;
+        let userdata = match &handleable_info.world {
+            Some(world) => parse_quote!(&#lifetime #world),
+            None => parse_quote!(arena_system::EmptyUserdata),
+        };
+
         Self {
             handleable: handleable_info,
             vis: &handleable_info.vis,
             ident: &handleable_info.handle_ident,
-            userdata: None,
+            userdata,
         }
     }
 
@@ -55,7 +59,7 @@ impl<'a> HandleInfo<'a> {
     }
 
     fn handle_decl(&self) -> TokenStream {
-        let HandleInfo { vis, ident, handleable, .. } = self;
+        let HandleInfo { vis, ident, handleable, userdata } = self;
 
         let lifetime = &handleable.lifetime;
         let handleable_generics_params = handleable.generics.params.iter();
@@ -66,7 +70,7 @@ impl<'a> HandleInfo<'a> {
         quote! {
             #vis struct #ident <#lifetime, #( #handleable_generics_params ),*> #where_clause {
                 __raw: arena_system::RawHandle<#lifetime, #handleable_type>,
-                tests: arena_system::Arena<Test<24, u32>>,
+                __userdata: #userdata,
             }
         }
     }
@@ -76,13 +80,14 @@ impl<'a> HandleInfo<'a> {
         let (impl_generics, _, where_clause) = iter_generics(&self.handleable.generics);
         let handleable_type = self.handleable.to_type();
         let handle_type = self.to_type();
+        let userdata = &self.userdata;
 
         quote! {
             impl<#lifetime, #( #impl_generics ),*> arena_system::Handle<#lifetime>
                 for #handle_type #where_clause
             {
                 type Type = #handleable_type;
-                type Userdata = arena_system::EmptyUserdata;
+                type Userdata = #userdata;
 
                 fn from_raw(
                     raw: arena_system::RawHandle<#lifetime, Self::Type>,
@@ -90,7 +95,7 @@ impl<'a> HandleInfo<'a> {
                 ) -> Self {
                     Self {
                         __raw: raw,
-                        tests: arena_system::Arena::new(),
+                        __userdata: userdata,
                     }
                 }
 
@@ -105,23 +110,39 @@ impl<'a> HandleInfo<'a> {
         let lifetime = &self.handleable.lifetime;
         let (impl_generics, _, where_clause) = iter_generics(&self.handleable.generics);
         let handle_type = self.to_type();
+        let kind = HandleKind::Borrowed {
+            lifetime: lifetime.clone(),
+            world: self.handleable.world.is_some(),
+            field_borrows: self.handleable.field_borrows,
+            history: self.handleable.history,
+            oplog: self.handleable.oplog,
+        };
 
         let getters = self
             .handleable
             .fields
             .iter()
             .map(|f| {
-                let getter = Getter::new(f, lifetime)?;
+                let getter = Getter::new(f, &kind)?;
 
                 Ok(getter.quote())
             })
-            .collect::<Result<Vec<_>>>();
+            .collect::<Result<Vec<_>>>()?;
+        let relation_getters = self
+            .handleable
+            .relations
+            .iter()
+            .map(|a| RelationGetter::parse_all(a, lifetime, self.handleable.world.is_some()))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .map(RelationGetter::quote);
 
-        getters.map(|getters| {
-            quote! {
-                impl<#lifetime, #( #impl_generics ),*> #handle_type #where_clause {
-                    #( #getters )*
-                }
+        Ok(quote! {
+            impl<#lifetime, #( #impl_generics ),*> #handle_type #where_clause {
+                #( #getters )*
+
+                #( #relation_getters )*
             }
         })
     }
@@ -130,13 +151,20 @@ impl<'a> HandleInfo<'a> {
         let lifetime = &self.handleable.lifetime;
         let (impl_generics, _, where_clause) = iter_generics(&self.handleable.generics);
         let handle_type = self.to_type();
+        let kind = HandleKind::Borrowed {
+            lifetime: lifetime.clone(),
+            world: self.handleable.world.is_some(),
+            field_borrows: self.handleable.field_borrows,
+            history: self.handleable.history,
+            oplog: self.handleable.oplog,
+        };
 
         let setters = self
             .handleable
             .fields
             .iter()
             .map(|f| {
-                let setter = Setter::new(f)?;
+                let setter = Setter::new(f, &kind, !self.handleable.key_indexes.is_empty())?;
 
                 Ok(setter.quote())
             })
@@ -3,8 +3,8 @@ use crate::util::iter_generics;
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote};
 use syn::{
-    parse_quote, punctuated::Punctuated, Data, DeriveInput, Field, Fields, GenericParam, Generics,
-    Ident, Lifetime, Token, Type, Visibility,
+    parse::Result, parse_quote, punctuated::Punctuated, Attribute, Data, DeriveInput, Field,
+    Fields, GenericParam, Generics, Ident, Lifetime, Token, Type, Visibility,
 };
 
 #[derive(Debug, Clone)]
@@ -16,11 +16,22 @@ pub struct HandleableInfo {
 
     pub handle_ident: Ident,
     pub lifetime: Lifetime,
+
+    pub world: Option<Type>,
+    pub relations: Vec<Attribute>,
+    pub key_indexes: Vec<Attribute>,
+
+    pub shared_generics: Generics,
+    pub shared_handle_ident: Option<Ident>,
+    pub soa_arena_ident: Option<Ident>,
+    pub field_borrows: bool,
+    pub history: bool,
+    pub oplog: bool,
 }
 
 impl HandleableInfo {
-    pub fn parse(input: syn::DeriveInput) -> Self {
-        let DeriveInput { vis, ident, mut generics, data, .. } = input;
+    pub fn parse(input: syn::DeriveInput) -> Result<Self> {
+        let DeriveInput { attrs, vis, ident, mut generics, data } = input;
 
         let fields = match data {
             Data::Struct(struct_data) => match struct_data.fields {
@@ -34,6 +45,73 @@ impl HandleableInfo {
             Data::Union(_) => unimplemented!("Unions are not supported"),
         };
 
+        let mut world = None;
+        let mut shared = false;
+        let mut soa = false;
+        let mut field_borrows = false;
+        let mut history = false;
+        let mut oplog = false;
+        attrs
+            .iter()
+            .filter(|a| a.path().is_ident("handleable"))
+            .try_for_each(|a| {
+                a.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("world") {
+                        world = Some(meta.value()?.parse::<Type>()?);
+
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("shared") {
+                        shared = true;
+
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("soa") {
+                        soa = true;
+
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("field_borrows") {
+                        field_borrows = true;
+
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("history") {
+                        history = true;
+
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("oplog") {
+                        oplog = true;
+
+                        return Ok(());
+                    }
+
+                    Err(meta.error("unrecognised handleable attribute"))
+                })
+            })?;
+
+        let relations = attrs
+            .iter()
+            .filter(|a| a.path().is_ident("handle_relations"))
+            .cloned()
+            .collect();
+
+        let key_indexes = attrs
+            .iter()
+            .filter(|a| a.path().is_ident("handle_index"))
+            .cloned()
+            .collect();
+
+        let shared_generics = generics.clone();
+        let shared_handle_ident = shared.then(|| format_ident!("{}SharedHandle", ident));
+        let soa_arena_ident = soa.then(|| format_ident!("{}SoaArena", ident));
+
         let lifetime = Lifetime::new("'arena", Span::call_site());
         generics.params.iter_mut().for_each(|g| {
             if let GenericParam::Type(ref mut t) = g {
@@ -43,7 +121,23 @@ impl HandleableInfo {
 
         let handle_ident = format_ident!("{}Handle", ident);
 
-        Self { vis, ident, generics, fields, handle_ident, lifetime }
+        Ok(Self {
+            vis,
+            ident,
+            generics,
+            fields,
+            handle_ident,
+            lifetime,
+            world,
+            relations,
+            key_indexes,
+            shared_generics,
+            shared_handle_ident,
+            soa_arena_ident,
+            field_borrows,
+            history,
+            oplog,
+        })
     }
 
     pub fn quote_impl(&self) -> TokenStream {
@@ -1,33 +1,88 @@
 extern crate proc_macro;
 
+mod field_index;
 mod getter;
-mod setter;
 mod handle;
 mod handleable;
+mod references;
+mod relation;
+mod replicate;
+mod setter;
+mod shared_handle;
+mod soa;
 mod util;
 
+use field_index::IndexesInfo;
 use handle::HandleInfo;
 use handleable::HandleableInfo;
+use references::ReferencesInfo;
+use replicate::ReplicateInfo;
+use shared_handle::SharedHandleInfo;
+use soa::SoaInfo;
 
 use quote::quote;
 use syn::{parse_macro_input, DeriveInput};
 
-#[proc_macro_derive(Handleable, attributes(handle_getter, handle_setter))]
+#[proc_macro_derive(
+    Handleable,
+    attributes(handleable, handle_getter, handle_setter, handle_index, handle_relations)
+)]
 pub fn derive_handleable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let handleable_info = HandleableInfo::parse(input);
+    let handleable_info = match HandleableInfo::parse(input) {
+        Ok(h) => h,
+        Err(err) => return err.to_compile_error().into(),
+    };
     let handle_info = HandleInfo::parse(&handleable_info);
+    let shared_handle_info = SharedHandleInfo::parse(&handleable_info);
+    let indexes_info = match IndexesInfo::parse(&handleable_info) {
+        Ok(i) => i,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let replicate_info = ReplicateInfo::parse(&handleable_info);
+    let soa_info = match SoaInfo::parse(&handleable_info) {
+        Ok(s) => s,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
     let handleable_impl = handleable_info.quote_impl();
     let handle = match handle_info.quote() {
         Ok(h) => h,
         Err(err) => return err.to_compile_error().into(),
     };
+    let shared_handle = match shared_handle_info.map(SharedHandleInfo::quote).transpose() {
+        Ok(h) => h,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let indexes = indexes_info.map(IndexesInfo::quote);
+    let replicate = replicate_info.map(ReplicateInfo::quote);
+    let soa = match soa_info.map(SoaInfo::quote).transpose() {
+        Ok(s) => s,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
     quote! {
         #handleable_impl
 
         #handle
+
+        #shared_handle
+
+        #indexes
+
+        #replicate
+
+        #soa
     }
     .into()
 }
+
+#[proc_macro_derive(References, attributes(reference))]
+pub fn derive_references(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match ReferencesInfo::parse(input) {
+        Ok(references_info) => references_info.quote().into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,123 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::Result, parse_quote, Data, DeriveInput, Error, Fields, Generics, Ident, Meta, Type,
+};
+
+pub struct ReferenceField {
+    pub ident: Ident,
+    pub target: Type,
+}
+
+pub struct ReferencesInfo {
+    pub ident: Ident,
+    pub generics: Generics,
+    pub fields: Vec<ReferenceField>,
+}
+
+impl ReferencesInfo {
+    pub fn parse(input: DeriveInput) -> Result<Self> {
+        let DeriveInput { ident, generics, data, .. } = input;
+
+        let fields = match data {
+            Data::Struct(struct_data) => match struct_data.fields {
+                Fields::Named(fields_named) => fields_named.named,
+                fields => {
+                    return Err(Error::new_spanned(
+                        fields,
+                        "Only structs with named fields are supported",
+                    ))
+                }
+            },
+            _ => return Err(Error::new_spanned(ident, "Only structs are supported")),
+        };
+
+        let fields = fields
+            .iter()
+            .flat_map(|f| {
+                f.attrs
+                    .iter()
+                    .filter(|a| a.path().is_ident("reference"))
+                    .map(move |a| (f, a))
+            })
+            .map(|(f, a)| {
+                let ident = f.ident.clone().expect("Structs with unnamed fields are not supported");
+                let target = match &a.meta {
+                    Meta::Path(_) => parse_quote!(Self),
+                    Meta::List(_) => a.parse_args::<Type>()?,
+                    Meta::NameValue(_) => {
+                        return Err(Error::new_spanned(a, "Expected `reference` or `reference(T)`"))
+                    }
+                };
+
+                Ok(ReferenceField { ident, target })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { ident, generics, fields })
+    }
+
+    pub fn quote(&self) -> TokenStream {
+        let ReferencesInfo { ident, generics, fields } = self;
+
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        let mut targets: Vec<&Type> = vec![];
+        fields.iter().for_each(|f| {
+            if !targets.contains(&&f.target) {
+                targets.push(&f.target);
+            }
+        });
+
+        let impls = targets.into_iter().map(|target| {
+            let target_fields = fields.iter().filter(|f| &f.target == target).collect::<Vec<_>>();
+
+            let visits = target_fields.iter().map(|f| {
+                let field_ident = &f.ident;
+                let field_name = field_ident.to_string();
+
+                quote! {
+                    arena_system::ReferenceField::visit(
+                        &self.#field_ident,
+                        &mut |index| visitor(#field_name, index),
+                    );
+                }
+            });
+            let visits_mut = target_fields.iter().map(|f| {
+                let field_ident = &f.ident;
+                let field_name = field_ident.to_string();
+
+                quote! {
+                    arena_system::ReferenceField::visit_mut(
+                        &mut self.#field_ident,
+                        &mut |index| visitor(#field_name, index),
+                    );
+                }
+            });
+
+            quote! {
+                impl #impl_generics arena_system::References<#target>
+                    for #ident #ty_generics #where_clause
+                {
+                    fn visit_references(
+                        &self,
+                        visitor: &mut dyn FnMut(&'static str, arena_system::Index),
+                    ) {
+                        #( #visits )*
+                    }
+
+                    fn visit_references_mut(
+                        &mut self,
+                        visitor: &mut dyn FnMut(&'static str, &mut arena_system::Index),
+                    ) {
+                        #( #visits_mut )*
+                    }
+                }
+            }
+        });
+
+        quote! {
+            #( #impls )*
+        }
+    }
+}
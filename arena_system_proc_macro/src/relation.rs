@@ -0,0 +1,107 @@
+use crate::util::parse_vis_attr;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    meta::ParseNestedMeta, parenthesized, parse::Result, parse_quote, Attribute, Error, Ident,
+    Lifetime, Type, Visibility,
+};
+
+pub struct RelationGetter {
+    pub vis: Visibility,
+    pub ident: Ident,
+    pub return_ty: Type,
+    pub body: TokenStream,
+}
+
+impl RelationGetter {
+    pub fn parse_all(a: &Attribute, lifetime: &Lifetime, world: bool) -> Result<Vec<Self>> {
+        if !world {
+            return Err(Error::new_spanned(
+                a,
+                "relation getters require `#[handleable(world = ...)]`",
+            ));
+        }
+
+        let mut getters = vec![];
+        a.parse_nested_meta(|meta| {
+            getters.push(Self::new(meta, lifetime)?);
+
+            Ok(())
+        })?;
+
+        Ok(getters)
+    }
+
+    fn new(meta: ParseNestedMeta, lifetime: &Lifetime) -> Result<Self> {
+        let fn_ident = meta.path.require_ident()?.clone();
+        let mut fn_vis = Visibility::Inherited;
+        let mut relation = None;
+        let mut accessor = None;
+
+        meta.parse_nested_meta(|meta| {
+            if meta.path.is_ident("vis") {
+                fn_vis = parse_vis_attr(meta)?;
+
+                return Ok(());
+            }
+
+            if meta.path.is_ident("relation") {
+                let relation_ident;
+                parenthesized!(relation_ident in meta.input);
+                relation = Some(relation_ident.parse::<Ident>()?);
+
+                return Ok(());
+            }
+
+            let mode = meta.path.get_ident().map(Ident::to_string);
+            match mode.as_deref() {
+                Some(mode @ ("targets" | "sources" | "target" | "source")) => {
+                    let element;
+                    parenthesized!(element in meta.input);
+                    let element_ty = element.parse::<Type>()?;
+
+                    accessor = Some((mode.to_string(), element_ty));
+
+                    Ok(())
+                }
+                _ => Err(meta.error("unrecognised relation attribute")),
+            }
+        })?;
+
+        let relation =
+            relation.ok_or_else(|| Error::new_spanned(&fn_ident, "expected `relation(...)`"))?;
+        let (mode, element_ty) = accessor.ok_or_else(|| {
+            Error::new_spanned(
+                &fn_ident,
+                "expected one of `targets(T)`, `sources(T)`, `target(T)` or `source(T)`",
+            )
+        })?;
+
+        let element_handle: Type =
+            parse_quote!(<#element_ty as arena_system::Handleable<#lifetime>>::Handle);
+        let (return_ty, method): (Type, Ident) = match mode.as_str() {
+            "targets" => (parse_quote!(Vec<#element_handle>), parse_quote!(target_handles)),
+            "sources" => (parse_quote!(Vec<#element_handle>), parse_quote!(source_handles)),
+            "target" => (parse_quote!(Option<#element_handle>), parse_quote!(target_handle)),
+            _ => (parse_quote!(Option<#element_handle>), parse_quote!(source_handle)),
+        };
+
+        let body = quote! {
+            use arena_system::Handle;
+            self.__userdata.#relation().#method(self.__userdata, self.index())
+        };
+
+        Ok(RelationGetter { vis: fn_vis, ident: fn_ident, return_ty, body })
+    }
+
+    pub fn quote(self) -> TokenStream {
+        let RelationGetter { vis, ident, return_ty, body } = self;
+
+        quote! {
+            #vis fn #ident(&'arena self) -> #return_ty {
+                #body
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::handleable::HandleableInfo;
+use crate::util::field_cell_inner;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub struct ReplicateInfo<'a> {
+    pub handleable: &'a HandleableInfo,
+}
+
+impl<'a> ReplicateInfo<'a> {
+    pub fn parse(handleable_info: &'a HandleableInfo) -> Option<Self> {
+        handleable_info.oplog.then_some(Self { handleable: handleable_info })
+    }
+
+    pub fn quote(self) -> TokenStream {
+        let ReplicateInfo { handleable } = self;
+
+        let ident = &handleable.ident;
+        let (impl_generics, ty_generics, where_clause) =
+            handleable.shared_generics.split_for_impl();
+
+        let arms = handleable.fields.iter().map(|f| {
+            let field_ident = f.ident.as_ref().unwrap();
+            let name = field_ident.to_string();
+            let assign = match field_cell_inner(&f.ty).filter(|_| handleable.field_borrows) {
+                Some(_) => quote!(*self.#field_ident.get_mut() = reader.read()?),
+                None => quote!(self.#field_ident = reader.read()?),
+            };
+
+            quote!(#name => #assign,)
+        });
+
+        quote! {
+            impl #impl_generics arena_system::Replicate for #ident #ty_generics #where_clause {
+                fn apply_field(
+                    &mut self,
+                    field: &str,
+                    reader: &mut arena_system::SnapshotReader<'_>,
+                ) -> arena_system::ArenaResult<()> {
+                    match field {
+                        #( #arms )*
+                        _ => {
+                            return Err(arena_system::ArenaError::SnapshotCorrupted(
+                                "unknown field in arena operation",
+                            ))
+                        }
+                    }
+
+                    Ok(())
+                }
+            }
+        }
+    }
+}
@@ -1,11 +1,8 @@
-use crate::util::{parse_name_attr, parse_vis_attr};
+use crate::util::{parse_name_attr, parse_vis_attr, HandleKind};
 
 use proc_macro2::TokenStream;
-use quote::{quote, quote_spanned, format_ident};
-use syn::{
-    parenthesized, parse::Result, spanned::Spanned, Field, Ident, Type,
-    Visibility,
-};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{parenthesized, parse::Result, spanned::Spanned, Error, Field, Ident, Type, Visibility};
 
 pub struct Setter {
     pub vis: Visibility,
@@ -15,14 +12,16 @@ pub struct Setter {
 }
 
 impl Setter {
-    pub fn new(f: &Field) -> Result<Setter> {
+    pub fn new(f: &Field, kind: &HandleKind, key_indexes: bool) -> Result<Setter> {
         let field_ident = f.ident.clone().expect("Structs with unnamed fields are not supported");
         let field_ty = &f.ty;
         let field_ty_span = field_ty.span();
 
+        let handle_trait = kind.handle_trait();
+
         let mut input_ty: Type = field_ty.clone();
         let mut fn_body = quote_spanned! { field_ty_span =>
-            use arena_system::Handle;
+            use #handle_trait;
             self.get_mut()
                 .map(|mut this_ref| {
                     this_ref.#field_ident = value;
@@ -59,7 +58,7 @@ impl Setter {
                             "value" => {
                                 input_ty = field_ty.clone();
                                 fn_body = quote_spanned! { field_ty_span =>
-                                    use arena_system::Handle;
+                                    use #handle_trait;
                                     self.get_mut()
                                         .map(|mut this_ref| {
                                             this_ref.#field_ident = value;
@@ -77,6 +76,132 @@ impl Setter {
                 })
             })?;
 
+        let field_index = f.attrs.iter().any(|a| a.path().is_ident("handle_index"));
+
+        // With `#[handleable(oplog)]`, writes are emitted once they're known to succeed.
+        let record_set = kind.oplog().then(
+            || quote!(self.arena().record_set(self.index(), stringify!(#field_ident), &value);),
+        );
+
+        if let Some(inner_ty) = kind.field_cell(field_ty) {
+            if field_index || key_indexes {
+                return Err(Error::new_spanned(
+                    field_ty,
+                    "field cells can't be updated together with `#[handle_index]` indexes",
+                ));
+            }
+
+            let assign = match kind.history() {
+                true => quote! {
+                    let index = self.index();
+                    let old = std::mem::replace(&mut *field, value);
+                    self.arena()
+                        .record_change(index, old, |element| element.#field_ident.get_mut());
+                },
+                false => quote!(*field = value;),
+            };
+
+            fn_body = quote_spanned! { field_ty_span =>
+                use arena_system::Handle;
+                self.get()
+                    .ok()
+                    .and_then(|this_ref| {
+                        let mut field = this_ref.#field_ident.try_borrow_mut().ok()?;
+                        #record_set
+                        #assign
+
+                        Some(())
+                    })
+                    .is_some()
+            };
+
+            return Ok(Setter {
+                vis: fn_vis,
+                ident: fn_ident,
+                input_ty: inner_ty.clone(),
+                body: fn_body,
+            });
+        }
+
+        // Soa arenas don't keep field indexes, so setters write straight into the column.
+        if let HandleKind::Soa { .. } = kind {
+            fn_body = quote_spanned! { field_ty_span =>
+                self.__arena.#field_ident
+                    .get_mut(self.__index)
+                    .map(|mut field| *field = value)
+                    .is_ok()
+            };
+
+            return Ok(Setter { vis: fn_vis, ident: fn_ident, input_ty, body: fn_body });
+        }
+
+        if kind.history() || kind.oplog() {
+            let assign = match kind.history() {
+                true => quote! {
+                    let old = std::mem::replace(&mut this_ref.#field_ident, value);
+                    self.arena()
+                        .record_change(self.index(), old, |element| &mut element.#field_ident);
+                },
+                false => quote!(this_ref.#field_ident = value;),
+            };
+
+            fn_body = quote_spanned! { field_ty_span =>
+                use #handle_trait;
+                self.get_mut()
+                    .map(|mut this_ref| {
+                        #record_set
+                        #assign
+                    })
+                    .is_ok()
+            };
+        }
+
+        if field_index || key_indexes {
+            let replace = field_index.then(
+                || quote!(indexes.#field_ident.replace(index, &element.#field_ident, &value)?;),
+            );
+            let (remove_keys, insert_keys) = match key_indexes {
+                true => (
+                    quote!(indexes.remove_keys(index, element);),
+                    quote! {
+                        if let Some(indexes) = indexes {
+                            indexes.insert_keys(index, element);
+                        }
+                    },
+                ),
+                false => (quote!(), quote!()),
+            };
+
+            // Undoing the change reindexes the element, see `Arena::record_change`.
+            let assign = match kind.history() {
+                true => quote! {
+                    let old = std::mem::replace(&mut element.#field_ident, value);
+                    arena.record_change(index, old, |element| &mut element.#field_ident);
+                },
+                false => quote!(element.#field_ident = value;),
+            };
+
+            let update = kind.with_arena(quote! {
+                arena.update_indexed(index, |element, mut indexes| {
+                    if let Some(indexes) = indexes.as_deref_mut() {
+                        #replace
+                        #remove_keys
+                    }
+                    #record_set
+                    #assign
+                    #insert_keys
+
+                    Ok(())
+                })
+            });
+
+            fn_body = quote_spanned! { field_ty_span =>
+                use #handle_trait;
+                let index = self.index();
+                #update.is_ok()
+            };
+        }
+
         Ok(Setter { vis: fn_vis, ident: fn_ident, input_ty, body: fn_body })
     }
 
@@ -89,4 +214,4 @@ impl Setter {
             }
         }
     }
-}
\ No newline at end of file
+}
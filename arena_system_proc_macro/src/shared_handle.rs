@@ -0,0 +1,156 @@
+use crate::getter::Getter;
+use crate::handleable::HandleableInfo;
+use crate::setter::Setter;
+use crate::util::{iter_generics, HandleKind};
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{parse::Result, parse_quote, Ident, Type, Visibility, WhereClause};
+
+pub struct SharedHandleInfo<'a> {
+    pub handleable: &'a HandleableInfo,
+
+    pub vis: &'a Visibility,
+    pub ident: &'a Ident,
+    pub arena: Ident,
+}
+
+impl<'a> SharedHandleInfo<'a> {
+    pub fn parse(handleable_info: &'a HandleableInfo) -> Option<Self> {
+        handleable_info.shared_handle_ident.as_ref().map(|ident| Self {
+            handleable: handleable_info,
+            vis: &handleable_info.vis,
+            ident,
+            arena: Ident::new("__A", Span::call_site()),
+        })
+    }
+
+    pub fn quote(self) -> Result<TokenStream> {
+        let handle_decl = self.handle_decl();
+        let handle_impl = self.handle_impl();
+        let handleable_impl = self.handleable_impl();
+        let accessors = self.accessors()?;
+
+        Ok(quote! {
+            #handle_decl
+
+            #handle_impl
+
+            #handleable_impl
+
+            #accessors
+        })
+    }
+
+    fn to_type(&self) -> Type {
+        let SharedHandleInfo { handleable, ident, arena, .. } = self;
+
+        let (_, ty_generics, _) = iter_generics(&handleable.shared_generics);
+
+        parse_quote!(#ident < #arena, #( #ty_generics ),* >)
+    }
+
+    fn where_clause(&self) -> WhereClause {
+        let arena = &self.arena;
+        let handleable_type = self.handleable.to_type();
+
+        let mut where_clause = self
+            .handleable
+            .shared_generics
+            .where_clause
+            .clone()
+            .unwrap_or_else(|| parse_quote!(where));
+        where_clause
+            .predicates
+            .push(parse_quote!(#arena: arena_system::SharedArena<#handleable_type>));
+
+        where_clause
+    }
+
+    fn handle_decl(&self) -> TokenStream {
+        let SharedHandleInfo { vis, ident, handleable, arena } = self;
+
+        let handleable_generics_params = handleable.shared_generics.params.iter();
+        let handleable_type = handleable.to_type();
+        let where_clause = self.where_clause();
+
+        quote! {
+            #vis struct #ident <#arena, #( #handleable_generics_params ),*> #where_clause {
+                __raw: arena_system::RawSharedHandle<#arena, #handleable_type>,
+            }
+        }
+    }
+
+    fn handle_impl(&self) -> TokenStream {
+        let arena = &self.arena;
+        let (impl_generics, _, _) = iter_generics(&self.handleable.shared_generics);
+        let where_clause = self.where_clause();
+        let handleable_type = self.handleable.to_type();
+        let handle_type = self.to_type();
+
+        quote! {
+            impl<#arena, #( #impl_generics ),*> arena_system::SharedHandle
+                for #handle_type #where_clause
+            {
+                type Type = #handleable_type;
+                type Arena = #arena;
+
+                fn from_raw(raw: arena_system::RawSharedHandle<#arena, Self::Type>) -> Self {
+                    Self { __raw: raw }
+                }
+
+                fn to_raw(&self) -> &arena_system::RawSharedHandle<#arena, Self::Type> {
+                    &self.__raw
+                }
+            }
+        }
+    }
+
+    fn handleable_impl(&self) -> TokenStream {
+        let SharedHandleInfo { ident, arena, .. } = self;
+        let (impl_generics, ty_generics, where_clause) =
+            iter_generics(&self.handleable.shared_generics);
+        let handleable_type = self.handleable.to_type();
+
+        quote! {
+            impl<#( #impl_generics ),*> arena_system::SharedHandleable
+                for #handleable_type #where_clause
+            {
+                type SharedHandle<#arena: arena_system::SharedArena<Self>> =
+                    #ident <#arena, #( #ty_generics ),*>;
+            }
+        }
+    }
+
+    fn accessors(&self) -> Result<TokenStream> {
+        let arena = &self.arena;
+        let (impl_generics, _, _) = iter_generics(&self.handleable.shared_generics);
+        let where_clause = self.where_clause();
+        let handle_type = self.to_type();
+        let kind = HandleKind::Shared {
+            arena: arena.clone(),
+            element: Box::new(self.handleable.to_type()),
+        };
+
+        let getters = self
+            .handleable
+            .fields
+            .iter()
+            .map(|f| Ok(Getter::new(f, &kind)?.quote()))
+            .collect::<Result<Vec<_>>>()?;
+        let setters = self
+            .handleable
+            .fields
+            .iter()
+            .map(|f| Ok(Setter::new(f, &kind, !self.handleable.key_indexes.is_empty())?.quote()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(quote! {
+            impl<#arena, #( #impl_generics ),*> #handle_type #where_clause {
+                #( #getters )*
+
+                #( #setters )*
+            }
+        })
+    }
+}
@@ -0,0 +1,243 @@
+use crate::getter::Getter;
+use crate::handleable::HandleableInfo;
+use crate::setter::Setter;
+use crate::util::HandleKind;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse::Result, parse_quote, Error, Ident, Type, Visibility};
+
+pub struct SoaInfo<'a> {
+    pub handleable: &'a HandleableInfo,
+
+    pub vis: &'a Visibility,
+    pub arena_ident: &'a Ident,
+    pub handle_ident: Ident,
+}
+
+impl<'a> SoaInfo<'a> {
+    pub fn parse(handleable_info: &'a HandleableInfo) -> Result<Option<Self>> {
+        let Some(arena_ident) = &handleable_info.soa_arena_ident else {
+            return Ok(None);
+        };
+
+        if !handleable_info.shared_generics.params.is_empty() {
+            return Err(Error::new_spanned(
+                &handleable_info.shared_generics,
+                "`#[handleable(soa)]` is not supported on generic structs",
+            ));
+        }
+
+        Ok(Some(Self {
+            handleable: handleable_info,
+            vis: &handleable_info.vis,
+            arena_ident,
+            handle_ident: format_ident!("{}SoaHandle", handleable_info.ident),
+        }))
+    }
+
+    pub fn quote(self) -> Result<TokenStream> {
+        let arena = self.arena();
+        let handle = self.handle()?;
+
+        Ok(quote! {
+            #arena
+
+            #handle
+        })
+    }
+
+    fn arena(&self) -> TokenStream {
+        let SoaInfo { handleable, vis, arena_ident, handle_ident } = self;
+
+        let element_ty = &handleable.ident;
+        let idents = handleable
+            .fields
+            .iter()
+            .map(|f| f.ident.as_ref().unwrap())
+            .collect::<Vec<_>>();
+        let tys = handleable.fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+        let iters = idents
+            .iter()
+            .map(|ident| format_ident!("iter_{}", ident))
+            .collect::<Vec<_>>();
+        let iters_mut = idents.iter().map(|ident| format_ident!("iter_{}_mut", ident));
+
+        quote! {
+            #vis struct #arena_ident {
+                __slots: arena_system::SoaSlots,
+                #( #idents: arena_system::SoaColumn<#tys>, )*
+            }
+
+            impl #arena_ident {
+                pub fn new() -> Self {
+                    Self {
+                        __slots: arena_system::SoaSlots::new(),
+                        #( #idents: arena_system::SoaColumn::new(), )*
+                    }
+                }
+
+                pub fn len(&self) -> usize {
+                    self.__slots.len()
+                }
+
+                pub fn is_empty(&self) -> bool {
+                    self.__slots.is_empty()
+                }
+
+                pub fn capacity(&self) -> usize {
+                    self.__slots.capacity()
+                }
+
+                pub fn contains(&self, index: arena_system::Index) -> bool {
+                    self.__slots.contains(index)
+                }
+
+                pub fn indices(&self) -> impl Iterator<Item = arena_system::Index> + '_ {
+                    self.__slots.indices()
+                }
+
+                pub fn add(&mut self, value: #element_ty) -> arena_system::Index {
+                    let index = self.__slots.allocate();
+                    let #element_ty { #( #idents ),* } = value;
+                    #( self.#idents.insert(index, #idents); )*
+
+                    index
+                }
+
+                pub fn remove(
+                    &mut self,
+                    index: arena_system::Index,
+                ) -> arena_system::ArenaResult<#element_ty> {
+                    self.__slots.release(index)?;
+
+                    Ok(#element_ty {
+                        #( #idents: self.#idents.take(index).expect("soa column lost its value"), )*
+                    })
+                }
+
+                pub fn handle(&self, index: arena_system::Index) -> #handle_ident<'_> {
+                    #handle_ident { __arena: self, __index: index }
+                }
+
+                pub fn handle_iter(&self) -> impl Iterator<Item = #handle_ident<'_>> + '_ {
+                    self.indices().map(|index| self.handle(index))
+                }
+
+                #(
+                    pub fn #iters(
+                        &self,
+                    ) -> impl Iterator<
+                        Item = (arena_system::Index, arena_system::ElementRef<'_, #tys>)
+                    > + '_ {
+                        self.#idents.iter()
+                    }
+
+                    pub fn #iters_mut(
+                        &self,
+                    ) -> impl Iterator<
+                        Item = (arena_system::Index, arena_system::ElementRefMut<'_, #tys>)
+                    > + '_ {
+                        self.#idents.iter_mut()
+                    }
+                )*
+            }
+
+            impl Default for #arena_ident {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
+            impl std::iter::FromIterator<#element_ty> for #arena_ident {
+                fn from_iter<I: IntoIterator<Item = #element_ty>>(iter: I) -> Self {
+                    let mut arena = Self::new();
+                    iter.into_iter().for_each(|value| {
+                        arena.add(value);
+                    });
+
+                    arena
+                }
+            }
+
+            impl std::fmt::Debug for #arena_ident {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.debug_struct(stringify!(#arena_ident))
+                        .field("len", &self.len())
+                        .field("capacity", &self.capacity())
+                        .finish()
+                }
+            }
+        }
+    }
+
+    fn handle(&self) -> Result<TokenStream> {
+        let SoaInfo { handleable, vis, arena_ident, handle_ident } = self;
+
+        let lifetime = &handleable.lifetime;
+        let kind = HandleKind::Soa { lifetime: lifetime.clone() };
+        let handle_type: Type = parse_quote!(#handle_ident<#lifetime>);
+
+        let getters = handleable
+            .fields
+            .iter()
+            .map(|f| Ok(Getter::new(f, &kind)?.quote()))
+            .collect::<Result<Vec<_>>>()?;
+        let setters = handleable
+            .fields
+            .iter()
+            .map(|f| Ok(Setter::new(f, &kind, false)?.quote()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(quote! {
+            #vis struct #handle_ident<#lifetime> {
+                __arena: &#lifetime #arena_ident,
+                __index: arena_system::Index,
+            }
+
+            impl<#lifetime> #handle_type {
+                pub fn arena(&self) -> &#lifetime #arena_ident {
+                    self.__arena
+                }
+
+                pub fn index(&self) -> arena_system::Index {
+                    self.__index
+                }
+
+                pub fn exists(&self) -> bool {
+                    self.__arena.contains(self.__index)
+                }
+
+                #( #getters )*
+
+                #( #setters )*
+            }
+
+            impl<#lifetime> Clone for #handle_type {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl<#lifetime> Copy for #handle_type {}
+
+            impl<#lifetime> PartialEq for #handle_type {
+                fn eq(&self, other: &Self) -> bool {
+                    std::ptr::eq(self.__arena, other.__arena) && self.__index == other.__index
+                }
+            }
+
+            impl<#lifetime> Eq for #handle_type {}
+
+            impl<#lifetime> std::fmt::Debug for #handle_type {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_fmt(format_args!(
+                        "{}({})",
+                        stringify!(#handle_ident),
+                        <arena_system::Index as Into<i64>>::into(self.__index),
+                    ))
+                }
+            }
+        })
+    }
+}
@@ -1,8 +1,107 @@
+use proc_macro2::TokenStream;
+use quote::quote;
 use syn::{
-    meta::ParseNestedMeta, parenthesized, parse::Result, GenericParam, Generics, Ident, Token,
-    VisRestricted, Visibility, WhereClause, Type, Error,
+    meta::ParseNestedMeta, parenthesized, parse::Result, GenericArgument, GenericParam, Generics,
+    Ident, Lifetime, PathArguments, Token, Type, VisRestricted, Visibility, WhereClause,
 };
 
+pub enum HandleKind {
+    Borrowed { lifetime: Lifetime, world: bool, field_borrows: bool, history: bool, oplog: bool },
+    Shared { arena: Ident, element: Box<Type> },
+    Soa { lifetime: Lifetime },
+}
+
+impl HandleKind {
+    pub fn receiver(&self) -> TokenStream {
+        match self {
+            HandleKind::Borrowed { lifetime, .. } => quote!(&#lifetime self),
+            HandleKind::Shared { .. } | HandleKind::Soa { .. } => quote!(&self),
+        }
+    }
+
+    pub fn handle_trait(&self) -> TokenStream {
+        match self {
+            HandleKind::Borrowed { .. } => quote!(arena_system::Handle),
+            HandleKind::Shared { .. } => quote!(arena_system::SharedHandle),
+            // Soa handles access columns directly instead of going through a handle trait.
+            HandleKind::Soa { .. } => quote!(),
+        }
+    }
+
+    pub fn with_arena(&self, body: TokenStream) -> TokenStream {
+        match self {
+            HandleKind::Borrowed { .. } => quote!({
+                let arena = self.arena();
+                #body
+            }),
+            HandleKind::Shared { .. } => {
+                quote!(arena_system::SharedArena::with_arena(self.arena(), |arena| #body))
+            }
+            HandleKind::Soa { .. } => unreachable!("soa arenas don't keep field indexes"),
+        }
+    }
+
+    pub fn ref_type(&self, ty: &Type) -> TokenStream {
+        match self {
+            HandleKind::Borrowed { lifetime, .. } | HandleKind::Soa { lifetime } => {
+                quote!(arena_system::ElementRef<#lifetime, #ty>)
+            }
+            HandleKind::Shared { arena, element } => {
+                quote!(<#arena as arena_system::SharedArena<#element>>::Ref<'_, #ty>)
+            }
+        }
+    }
+
+    // With `#[handleable(field_borrows)]`, fields of type `FieldCell<T>` are borrowed on their own
+    // and their accessors work with the inner type.
+    pub fn field_cell<'a>(&self, ty: &'a Type) -> Option<&'a Type> {
+        let HandleKind::Borrowed { field_borrows: true, .. } = self else {
+            return None;
+        };
+
+        field_cell_inner(ty)
+    }
+
+    // With `#[handleable(history)]`, setters record the replaced value into the arena history.
+    pub fn history(&self) -> bool {
+        matches!(self, HandleKind::Borrowed { history: true, .. })
+    }
+
+    // With `#[handleable(oplog)]`, setters emit their writes into the arena operation log.
+    pub fn oplog(&self) -> bool {
+        matches!(self, HandleKind::Borrowed { oplog: true, .. })
+    }
+
+    pub fn map_ref(&self) -> TokenStream {
+        match self {
+            HandleKind::Borrowed { .. } | HandleKind::Soa { .. } => {
+                quote!(arena_system::ElementRef::map)
+            }
+            HandleKind::Shared { arena, element } => {
+                quote!(<#arena as arena_system::SharedArena<#element>>::map_ref)
+            }
+        }
+    }
+}
+
+pub fn field_cell_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "FieldCell" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 pub fn iter_generics(
     generics: &Generics,
 ) -> (std::vec::IntoIter<&GenericParam>, std::vec::IntoIter<proc_macro2::Ident>, Option<WhereClause>)
@@ -60,60 +159,3 @@ pub fn parse_vis_attr(meta: ParseNestedMeta) -> Result<Visibility> {
 
     Err(meta.error("the given attribute isn't visibility"))
 }
-
-pub fn unwrap_type<'a>(wrapper: &str, ty: &'a Type) -> Result<&'a Type> {
-    match ty {
-        syn::Type::Path(type_path) => {
-            let path_segment = type_path
-                .path
-                .segments
-                .iter()
-                .find(|ps| ps.ident.to_string() == wrapper.to_string())
-                .ok_or(Error::new_spanned(
-                    ty,
-                    format!(
-                        "Expected `{wrapper}`, found `{}`",
-                        type_path_to_string(type_path)
-                    ),
-                ))?;
-
-            match path_segment.arguments {
-                syn::PathArguments::AngleBracketed(ref angle_bracketed) => {
-                    if angle_bracketed.args.len() > 1 {
-                        return Err(Error::new_spanned(
-                            ty,
-                            format!("Expected `{wrapper}<T>`, found `{wrapper}<T1, T2, ..., Tn>`"),
-                        ));
-                    }
-
-                    match angle_bracketed.args.first() {
-                        Some(syn::GenericArgument::Type(ty)) => Ok(ty),
-                        _ => Err(Error::new_spanned(
-                            ty,
-                            format!("Expected `{wrapper}<T>`, found `{wrapper}`"),
-                        )),
-                    }
-                }
-                syn::PathArguments::Parenthesized(_) => Err(Error::new_spanned(
-                    ty,
-                    format!("Expected `{wrapper}<T>`, found `{wrapper}(T1, T2, ..., Tn)`"),
-                )),
-                syn::PathArguments::None => Err(Error::new_spanned(
-                    ty,
-                    format!("Expected `{wrapper}<T>`, found `{wrapper}`"),
-                )),
-            }
-        }
-        _ => Err(Error::new_spanned(ty, "Expected type path")),
-    }
-}
-
-pub fn type_path_to_string(type_path: &syn::TypePath) -> String {
-    type_path
-        .path
-        .segments
-        .iter()
-        .map(|p| p.ident.to_string())
-        .collect::<Vec<String>>()
-        .join("::")
-}
\ No newline at end of file
@@ -1,54 +1,124 @@
-use crate::{ArenaError, ArenaResult};
-use crate::{Handle, RawHandle};
-use crate::{Handleable, Index};
+use crate::history::History;
+use crate::oplog::OpLog;
+use crate::{ArenaError, ArenaResult, DefaultStorage, DenseStorage, Storage, TryClone};
+use crate::{DetachedHandle, Handle, RawHandle};
+use crate::{ElementIndexes, ElementRef, ElementRefMut, Handleable, Index};
 
-use std::{convert, iter};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::{convert, fmt, iter};
 
-use vec_cell::{ElementRef, ElementRefMut, Flatten, VecCell};
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArenaId(u64);
+
+impl ArenaId {
+    fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+// Handles refer to arenas through this type, so they work the same for every storage backend.
+pub type DynArena<'arena, T> = Arena<T, dyn Storage<T> + 'arena>;
 
 #[derive(Debug)]
-pub struct Arena<T> {
-    data: VecCell<Option<T>>,
-    free: Vec<Index>,
+pub struct Arena<T, S: ?Sized = DefaultStorage<T>> {
+    id: ArenaId,
+    element_debug: Option<fn(&T) -> String>,
+    indexes: Option<RefCell<Box<dyn ElementIndexes<T>>>>,
+    history: Option<RefCell<History<T>>>,
+    op_log: Option<RefCell<OpLog<T>>>,
+    storage: S,
 }
 
-impl<'arena, T: Handleable<'arena>> Arena<T> {
+impl<T> Arena<T> {
     pub fn new() -> Self {
-        Self { data: VecCell::new(), free: vec![] }
+        Self::with_storage(DenseStorage::new())
+    }
+}
+
+impl<T, S: Storage<T>> Arena<T, S> {
+    pub fn with_storage(storage: S) -> Self {
+        Self {
+            id: ArenaId::next(),
+            element_debug: None,
+            indexes: None,
+            history: None,
+            op_log: None,
+            storage,
+        }
+    }
+}
+
+impl<T, S: ?Sized + Storage<T>> Arena<T, S> {
+    pub fn id(&self) -> ArenaId {
+        self.id
     }
 
     pub fn len(&self) -> usize {
-        self.data.len() - self.free.len()
+        self.storage.len()
     }
 
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
-    pub fn handle(
-        &'arena self,
-        index: Index,
-        userdata: <T::Handle as Handle<'arena>>::Userdata,
-    ) -> T::Handle {
-        let raw_handle = RawHandle::new(self, index);
+    pub fn capacity(&self) -> usize {
+        self.storage.capacity()
+    }
 
-        T::Handle::from_raw(raw_handle, userdata)
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    pub fn indices(&self) -> impl Iterator<Item = Index> + '_ {
+        self.storage.occupied().map(Index::from)
     }
 
+    // Elements which are mutably borrowed are skipped.
+    pub fn iter(&self) -> impl Iterator<Item = (Index, ElementRef<'_, T>)> + '_ {
+        self.indices().filter_map(|index| Some((index, self.lookup(index).ok()?)))
+    }
+
+    // Panics if `value` violates a unique index, see `try_add`.
     pub fn add(&mut self, value: T) -> Index {
-        match self.free.pop() {
-            Some(free_index) => {
-                let mut free_place = self.lookup_mut(free_index).unwrap();
-                *free_place = value;
+        self.try_add(value).expect("failed to add element")
+    }
 
-                free_index
-            }
-            None => {
-                self.data.push(Some(value));
+    pub fn try_add(&mut self, value: T) -> ArenaResult<Index> {
+        if let Some(indexes) = &mut self.indexes {
+            indexes.get_mut().insert(Index::from(self.storage.next_slot()), &value)?;
+        }
 
-                Index::from(self.data.len() - 1)
-            }
+        self.log_add(Index::from(self.storage.next_slot()), &value);
+        let index = Index::from(self.storage.insert(value));
+        self.record_slot(index, None);
+
+        Ok(index)
+    }
+
+    // Whether `add_at` can use `index`, i.e. it's vacant or the next slot to be appended.
+    pub(crate) fn is_vacant(&self, index: Index) -> bool {
+        !index.is_invalid() && {
+            let slot: usize = index.into();
+
+            slot <= self.storage.capacity() && !self.storage.is_occupied(slot)
+        }
+    }
+
+    // Like `try_add`, but into the slot at `index`, which has to be vacant. Used to replay
+    // changes which were recorded together with the slots of added elements.
+    pub(crate) fn add_at(&mut self, index: Index, value: T) -> ArenaResult<()> {
+        if let Some(indexes) = &mut self.indexes {
+            indexes.get_mut().insert(index, &value)?;
         }
+
+        self.log_add(index, &value);
+        self.storage.insert_at(index.into(), value);
+        self.record_slot(index, None);
+
+        Ok(())
     }
 
     pub fn remove(&mut self, index: Index) -> ArenaResult<T> {
@@ -56,48 +126,175 @@ impl<'arena, T: Handleable<'arena>> Arena<T> {
             return Err(ArenaError::InvalidIndexUsage);
         }
 
-        let mut element = self.data.try_take(index.into()).unwrap();
-        match element.take() {
-            Some(element) => Ok(element),
+        match self.storage.take(index.into()) {
+            Some(element) => {
+                if let Some(indexes) = &mut self.indexes {
+                    indexes.get_mut().remove(index, &element);
+                }
+                self.record_slot(index, Some(&element));
+                self.log_remove(index);
+
+                Ok(element)
+            }
             None => Err(ArenaError::RemovedElementAccess),
         }
     }
 
-    pub fn handle_iter(
-        &'arena self,
-        userdata: <T::Handle as Handle<'arena>>::Userdata,
-    ) -> HandleIter<'arena, T> {
-        HandleIter {
-            arena: self,
-            userdata,
-            last_index: Index::new(0),
+    pub fn lookup(&self, index: Index) -> ArenaResult<ElementRef<'_, T>> {
+        if index.is_invalid() {
+            return Err(ArenaError::InvalidIndexUsage);
         }
+
+        self.storage.lookup(index.into())
     }
 
-    pub fn lookup(&self, index: Index) -> ArenaResult<ElementRef<'_, T>> {
+    pub fn lookup_mut(&self, index: Index) -> ArenaResult<ElementRefMut<'_, T>> {
         if index.is_invalid() {
             return Err(ArenaError::InvalidIndexUsage);
         }
 
-        self.data.try_borrow(index.into()).flatten().map_err(ArenaError::from)
+        self.storage.lookup_mut(index.into())
     }
 
-    pub fn lookup_mut(&self, index: Index) -> ArenaResult<ElementRefMut<'_, T>> {
+    pub fn contains(&self, index: Index) -> bool {
+        !index.is_invalid() && self.storage.is_occupied(index.into())
+    }
+
+    pub fn detach(&self, index: Index) -> ArenaResult<DetachedHandle<T>> {
         if index.is_invalid() {
             return Err(ArenaError::InvalidIndexUsage);
         }
 
-        self.data.try_borrow_mut(index.into()).flatten().map_err(ArenaError::from)
+        if !self.contains(index) {
+            return Err(ArenaError::RemovedElementAccess);
+        }
+
+        Ok(DetachedHandle::new(self.id, index, self.generation(index)))
+    }
+
+    // Lets `ErasedArena::debug_element` format elements of this arena.
+    pub fn enable_element_debug(&mut self)
+    where
+        T: fmt::Debug,
+    {
+        self.element_debug = Some(|element| format!("{element:?}"));
+    }
+
+    pub(crate) fn element_debug(&self) -> Option<fn(&T) -> String> {
+        self.element_debug
+    }
+
+    pub(crate) fn generation(&self, index: Index) -> u32 {
+        self.storage.generation(index.into())
+    }
+
+    pub(crate) fn indexes(&self) -> Option<&RefCell<Box<dyn ElementIndexes<T>>>> {
+        self.indexes.as_ref()
+    }
+
+    pub(crate) fn set_indexes(&mut self, indexes: Box<dyn ElementIndexes<T>>) {
+        self.indexes = Some(RefCell::new(indexes));
+    }
+
+    pub(crate) fn history(&self) -> Option<&RefCell<History<T>>> {
+        self.history.as_ref()
+    }
+
+    pub(crate) fn history_mut(&mut self) -> &mut Option<RefCell<History<T>>> {
+        &mut self.history
+    }
+
+    pub(crate) fn op_log(&self) -> Option<&RefCell<OpLog<T>>> {
+        self.op_log.as_ref()
+    }
+
+    pub(crate) fn op_log_mut(&mut self) -> &mut Option<RefCell<OpLog<T>>> {
+        &mut self.op_log
+    }
+}
+
+pub trait AsDynArena<'arena, T> {
+    fn as_dyn(&'arena self) -> &'arena DynArena<'arena, T>;
+}
+
+impl<'arena, T, S: Storage<T> + 'arena> AsDynArena<'arena, T> for Arena<T, S> {
+    fn as_dyn(&'arena self) -> &'arena DynArena<'arena, T> {
+        self
+    }
+}
+
+impl<'arena, T> AsDynArena<'arena, T> for DynArena<'arena, T> {
+    fn as_dyn(&'arena self) -> &'arena DynArena<'arena, T> {
+        self
+    }
+}
+
+impl<'arena, T: Handleable<'arena>, S: ?Sized + Storage<T>> Arena<T, S>
+where
+    Self: AsDynArena<'arena, T>,
+{
+    pub fn handle(
+        &'arena self,
+        index: Index,
+        userdata: <T::Handle as Handle<'arena>>::Userdata,
+    ) -> T::Handle {
+        let raw_handle = RawHandle::new(self.as_dyn(), index);
+
+        T::Handle::from_raw(raw_handle, userdata)
+    }
+
+    pub fn handle_iter(
+        &'arena self,
+        userdata: <T::Handle as Handle<'arena>>::Userdata,
+    ) -> HandleIter<'arena, T> {
+        HandleIter { arena: self.as_dyn(), userdata, last_index: Index::new(0) }
+    }
+}
+
+// Elements are cloned with `Clone`, so cloning an element with a mutably borrowed `FieldCell` still
+// panics.
+impl<T: Clone, S: Storage<T> + TryClone> TryClone for Arena<T, S> {
+    fn try_clone(&self) -> ArenaResult<Self> {
+        let indexes = match &self.indexes {
+            Some(indexes) => {
+                let indexes = indexes.try_borrow().map_err(|_| ArenaError::ArenaBorrowed)?;
+
+                Some(RefCell::new(indexes.clone_box()))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            id: ArenaId::next(),
+            element_debug: self.element_debug,
+            indexes,
+            history: None,
+            op_log: None,
+            storage: self.storage.try_clone()?,
+        })
+    }
+}
+
+// Panics if an element is borrowed mutably, see `try_clone`.
+impl<T: Clone, S: Storage<T> + TryClone> Clone for Arena<T, S> {
+    fn clone(&self) -> Self {
+        self.try_clone().expect("failed to borrow element for cloning")
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl<'arena, T: Handleable<'arena>> convert::From<Vec<T>> for Arena<T> {
+impl<T> convert::From<Vec<T>> for Arena<T> {
     fn from(data: Vec<T>) -> Self {
         data.into_iter().collect()
     }
 }
 
-impl<'arena, T: Handleable<'arena>> iter::FromIterator<T> for Arena<T> {
+impl<T> iter::FromIterator<T> for Arena<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
         let mut arena = Arena::new();
         iter.into_iter().for_each(|value| {
@@ -109,7 +306,7 @@ impl<'arena, T: Handleable<'arena>> iter::FromIterator<T> for Arena<T> {
 }
 
 pub struct HandleIter<'arena, T: Handleable<'arena>> {
-    arena: &'arena Arena<T>,
+    arena: &'arena DynArena<'arena, T>,
     userdata: <T::Handle as Handle<'arena>>::Userdata,
 
     last_index: Index,
@@ -120,7 +317,7 @@ impl<'arena, T: Handleable<'arena>> iter::Iterator for HandleIter<'arena, T> {
 
     fn next(&mut self) -> Option<Self::Item> {
         let last_index: usize = self.last_index.into();
-        if last_index >= self.arena.data.len() {
+        if last_index >= self.arena.capacity() {
             return None;
         }
 
@@ -0,0 +1,250 @@
+use crate::element::BorrowFlag;
+use crate::{
+    Arena, ArenaError, ArenaResult, BorrowError, ElementRef, ElementRefMut, Index, Storage,
+    TryClone,
+};
+
+use std::cell::{Cell, UnsafeCell};
+use std::fmt;
+use std::ptr::NonNull;
+
+struct Slot<T> {
+    flag: BorrowFlag,
+    generation: u32,
+    value: UnsafeCell<Option<T>>,
+}
+
+// Slots live in fixed-size chunks which are never reallocated, so elements keep their addresses
+// and new slots can be appended through `&self` while other elements are borrowed.
+pub struct ChunkedStorage<T> {
+    chunk_size: usize,
+    chunks: UnsafeCell<Vec<NonNull<[Slot<T>]>>>,
+    len: Cell<usize>,
+    free: Vec<usize>,
+}
+
+impl<T> ChunkedStorage<T> {
+    pub fn new(chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+
+        Self { chunk_size, chunks: UnsafeCell::new(vec![]), len: Cell::new(0), free: vec![] }
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    // Appends a new slot even if there are vacant ones.
+    pub fn push(&self, value: T) -> usize {
+        self.push_slot(Some(value), 0)
+    }
+
+    fn push_slot(&self, value: Option<T>, generation: u32) -> usize {
+        let index = self.len.get();
+        let (chunk, offset) = (index / self.chunk_size, index % self.chunk_size);
+
+        // SAFETY: the list of chunks is only accessed inside `ChunkedStorage` methods, none of
+        // which keep a reference to it, and pushing to it doesn't move the chunks themselves.
+        let chunks = unsafe { &mut *self.chunks.get() };
+        if chunk == chunks.len() {
+            let slots = (0..self.chunk_size)
+                .map(|_| Slot {
+                    flag: BorrowFlag::default(),
+                    generation: 0,
+                    value: UnsafeCell::new(None),
+                })
+                .collect::<Box<[_]>>();
+            chunks.push(NonNull::from(Box::leak(slots)));
+        }
+
+        // SAFETY: the slot is past `len`, so nobody can borrow it yet.
+        let slot = unsafe { &mut *chunks[chunk].as_ptr().cast::<Slot<T>>().add(offset) };
+        *slot.value.get_mut() = value;
+        slot.generation = generation;
+        self.len.set(index + 1);
+
+        index
+    }
+
+    fn slot(&self, index: usize) -> Option<&Slot<T>> {
+        if index >= self.len.get() {
+            return None;
+        }
+
+        // SAFETY: see `push`; slots below `len` are initialized and never move.
+        let chunks = unsafe { &*self.chunks.get() };
+        let chunk = chunks[index / self.chunk_size];
+
+        Some(unsafe { &*chunk.as_ptr().cast::<Slot<T>>().add(index % self.chunk_size) })
+    }
+
+    fn slot_mut(&mut self, index: usize) -> Option<&mut Slot<T>> {
+        if index >= self.len.get() {
+            return None;
+        }
+
+        let chunk = self.chunks.get_mut()[index / self.chunk_size];
+
+        // SAFETY: `&mut self` rules out any other access to the slot.
+        Some(unsafe { &mut *chunk.as_ptr().cast::<Slot<T>>().add(index % self.chunk_size) })
+    }
+}
+
+impl<T> Storage<T> for ChunkedStorage<T> {
+    fn len(&self) -> usize {
+        self.len.get() - self.free.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.len.get()
+    }
+
+    fn next_slot(&self) -> usize {
+        self.free.last().copied().unwrap_or_else(|| self.len.get())
+    }
+
+    fn insert(&mut self, value: T) -> usize {
+        match self.free.pop() {
+            Some(slot) => {
+                *self.slot_mut(slot).unwrap().value.get_mut() = Some(value);
+
+                slot
+            }
+            None => self.push(value),
+        }
+    }
+
+    fn insert_at(&mut self, slot: usize, value: T) {
+        if slot == self.len.get() {
+            self.push(value);
+
+            return;
+        }
+
+        let position = self.free.iter().rposition(|&free| free == slot).expect("slot is occupied");
+        self.free.remove(position);
+        *self.slot_mut(slot).unwrap().value.get_mut() = Some(value);
+    }
+
+    fn take(&mut self, slot: usize) -> Option<T> {
+        let Slot { generation, value, .. } = self.slot_mut(slot)?;
+        let element = value.get_mut().take()?;
+        *generation = generation.wrapping_add(1);
+        self.free.push(slot);
+
+        Some(element)
+    }
+
+    fn lookup(&self, slot: usize) -> ArenaResult<ElementRef<'_, T>> {
+        let slot = self.slot(slot).ok_or(ArenaError::RemovedElementAccess)?;
+        let flag = slot.flag.try_borrow().ok_or(BorrowError::ElementAlreadyBorrowedMutably)?;
+
+        // SAFETY: the shared borrow flag is held by the returned reference.
+        match unsafe { &*slot.value.get() } {
+            Some(element) => Ok(ElementRef::from_flag(element, flag)),
+            None => Err(ArenaError::RemovedElementAccess),
+        }
+    }
+
+    fn lookup_mut(&self, slot: usize) -> ArenaResult<ElementRefMut<'_, T>> {
+        let slot = self.slot(slot).ok_or(ArenaError::RemovedElementAccess)?;
+        let flag = slot.flag.try_borrow_mut().ok_or(BorrowError::ElementAlreadyBorrowed)?;
+
+        // SAFETY: the exclusive borrow flag is held by the returned reference.
+        match unsafe { &mut *slot.value.get() } {
+            Some(element) => Ok(ElementRefMut::from_flag(element, flag)),
+            None => Err(ArenaError::RemovedElementAccess),
+        }
+    }
+
+    fn is_occupied(&self, slot: usize) -> bool {
+        self.slot(slot).is_some_and(|slot| {
+            // SAFETY: the value is only read if nobody borrows it.
+            slot.flag.is_borrowed() || unsafe { (*slot.value.get()).is_some() }
+        })
+    }
+
+    fn generation(&self, slot: usize) -> u32 {
+        self.slot(slot).map_or(0, |slot| slot.generation)
+    }
+
+    fn occupied(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new((0..self.len.get()).filter(|&slot| self.is_occupied(slot)))
+    }
+
+    fn vacant(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(self.free.iter().rev().copied())
+    }
+}
+
+impl<T> Arena<T, ChunkedStorage<T>> {
+    pub fn chunked(chunk_size: usize) -> Self {
+        Self::with_storage(ChunkedStorage::new(chunk_size))
+    }
+
+    // Elements of chunked arenas never move, so they can be added while others are borrowed.
+    // Unlike `add`, this never reuses slots of removed elements.
+    pub fn push(&self, value: T) -> ArenaResult<Index> {
+        let index = Index::from(self.storage().capacity());
+        if let Some(indexes) = self.indexes() {
+            let mut indexes = indexes.try_borrow_mut().map_err(|_| ArenaError::ArenaBorrowed)?;
+            indexes.insert(index, &value)?;
+        }
+        self.log_add(index, &value);
+        self.storage().push(value);
+        self.record_slot(index, None);
+
+        Ok(index)
+    }
+}
+
+impl<T: Clone> TryClone for ChunkedStorage<T> {
+    fn try_clone(&self) -> ArenaResult<Self> {
+        let mut cloned = Self::new(self.chunk_size);
+        (0..self.len.get()).try_for_each(|slot| {
+            let element = match self.lookup(slot) {
+                Ok(element) => Some(element.clone()),
+                Err(err) if self.is_occupied(slot) => return Err(err),
+                Err(_) => None,
+            };
+            cloned.push_slot(element, self.generation(slot));
+
+            Ok(())
+        })?;
+
+        cloned.free = self.free.clone();
+
+        Ok(cloned)
+    }
+}
+
+// Panics if an element is borrowed mutably, see `try_clone`.
+impl<T: Clone> Clone for ChunkedStorage<T> {
+    fn clone(&self) -> Self {
+        self.try_clone().expect("failed to borrow element for cloning")
+    }
+}
+
+impl<T> Drop for ChunkedStorage<T> {
+    fn drop(&mut self) {
+        self.chunks.get_mut().drain(..).for_each(|chunk| {
+            // SAFETY: every chunk was leaked from a box in `push` and is dropped only once.
+            drop(unsafe { Box::from_raw(chunk.as_ptr()) });
+        });
+    }
+}
+
+// SAFETY: the storage owns its chunks like a `Vec` owns its buffer, and chunks are never
+// reallocated or moved while `&self` borrows exist, so elements only move between threads together
+// with the storage. It isn't `Sync` because of the unsynchronized `Cell`s and borrow flags.
+unsafe impl<T: Send> Send for ChunkedStorage<T> {}
+
+impl<T> fmt::Debug for ChunkedStorage<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkedStorage")
+            .field("chunk_size", &self.chunk_size)
+            .field("len", &self.len.get())
+            .field("free", &self.free)
+            .finish()
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{Arena, ArenaError, ArenaId, ArenaResult, AsDynArena};
+use crate::{Handle, Handleable, Index, RawHandle, Storage};
+
+use std::marker::PhantomData;
+use std::{cmp, fmt, hash};
+
+pub struct DetachedHandle<T> {
+    arena_id: ArenaId,
+    index: Index,
+    generation: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> DetachedHandle<T> {
+    pub(crate) fn new(arena_id: ArenaId, index: Index, generation: u32) -> Self {
+        Self { arena_id, index, generation, _marker: PhantomData }
+    }
+
+    pub fn arena_id(&self) -> ArenaId {
+        self.arena_id
+    }
+
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    pub fn attach<'arena, S>(self, arena: &'arena Arena<T, S>) -> ArenaResult<T::Handle>
+    where
+        T: Handleable<'arena>,
+        S: ?Sized + Storage<T>,
+        Arena<T, S>: AsDynArena<'arena, T>,
+        <T::Handle as Handle<'arena>>::Userdata: Default,
+    {
+        self.attach_with(arena, Default::default())
+    }
+
+    pub fn attach_with<'arena, S>(
+        self,
+        arena: &'arena Arena<T, S>,
+        userdata: <T::Handle as Handle<'arena>>::Userdata,
+    ) -> ArenaResult<T::Handle>
+    where
+        T: Handleable<'arena>,
+        S: ?Sized + Storage<T>,
+        Arena<T, S>: AsDynArena<'arena, T>,
+    {
+        self.check(arena)?;
+
+        Ok(T::Handle::from_raw(RawHandle::new(arena.as_dyn(), self.index), userdata))
+    }
+
+    // Whether the element is still in `arena`, i.e. `attach` would succeed.
+    pub fn is_live<S: ?Sized + Storage<T>>(&self, arena: &Arena<T, S>) -> bool {
+        self.check(arena).is_ok()
+    }
+
+    fn check<S: ?Sized + Storage<T>>(&self, arena: &Arena<T, S>) -> ArenaResult<()> {
+        if arena.id() != self.arena_id {
+            return Err(ArenaError::ForeignIndex);
+        }
+
+        if !arena.contains(self.index) || arena.generation(self.index) != self.generation {
+            return Err(ArenaError::StaleIndex);
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> fmt::Debug for DetachedHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!(
+            "DetachedHandle({}v{})",
+            <Index as Into<i64>>::into(self.index),
+            self.generation
+        ))
+    }
+}
+
+impl<T> Clone for DetachedHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for DetachedHandle<T> {}
+
+impl<T> cmp::PartialEq for DetachedHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.arena_id == other.arena_id
+            && self.index == other.index
+            && self.generation == other.generation
+    }
+}
+
+impl<T> cmp::Eq for DetachedHandle<T> {}
+
+impl<T> hash::Hash for DetachedHandle<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.arena_id.hash(state);
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
@@ -0,0 +1,159 @@
+use std::cell::Cell;
+use std::{fmt, ops};
+
+// Elements are borrowed either through `VecCell` or through flags kept by storages which manage
+// their own memory, such as chunked storage. Replacing the previously re-exported `vec_cell` guards
+// of the same names is a breaking change for code which relies on them being `vec_cell` types;
+// `From` converts those guards into these.
+pub struct ElementRef<'a, T> {
+    inner: RefInner<'a, T>,
+}
+
+enum RefInner<'a, T> {
+    Cell(vec_cell::ElementRef<'a, T>),
+    Flag(&'a T, SharedFlag<'a>),
+}
+
+impl<'a, T> ElementRef<'a, T> {
+    pub fn map<U>(this: Self, f: impl FnOnce(&T) -> &U) -> ElementRef<'a, U> {
+        let inner = match this.inner {
+            RefInner::Cell(element) => RefInner::Cell(vec_cell::ElementRef::map(element, f)),
+            RefInner::Flag(element, flag) => RefInner::Flag(f(element), flag),
+        };
+
+        ElementRef { inner }
+    }
+
+    pub(crate) fn from_flag(element: &'a T, flag: SharedFlag<'a>) -> Self {
+        Self { inner: RefInner::Flag(element, flag) }
+    }
+}
+
+impl<'a, T> From<vec_cell::ElementRef<'a, T>> for ElementRef<'a, T> {
+    fn from(element: vec_cell::ElementRef<'a, T>) -> Self {
+        Self { inner: RefInner::Cell(element) }
+    }
+}
+
+impl<T> ops::Deref for ElementRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match &self.inner {
+            RefInner::Cell(element) => element,
+            RefInner::Flag(element, _) => element,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ElementRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+pub struct ElementRefMut<'a, T> {
+    inner: RefMutInner<'a, T>,
+}
+
+enum RefMutInner<'a, T> {
+    Cell(vec_cell::ElementRefMut<'a, T>),
+    Flag(&'a mut T, ExclusiveFlag<'a>),
+}
+
+impl<'a, T> ElementRefMut<'a, T> {
+    pub fn map<U>(this: Self, f: impl FnOnce(&mut T) -> &mut U) -> ElementRefMut<'a, U> {
+        let inner = match this.inner {
+            RefMutInner::Cell(element) => {
+                RefMutInner::Cell(vec_cell::ElementRefMut::map(element, f))
+            }
+            RefMutInner::Flag(element, flag) => RefMutInner::Flag(f(element), flag),
+        };
+
+        ElementRefMut { inner }
+    }
+
+    pub(crate) fn from_flag(element: &'a mut T, flag: ExclusiveFlag<'a>) -> Self {
+        Self { inner: RefMutInner::Flag(element, flag) }
+    }
+}
+
+impl<'a, T> From<vec_cell::ElementRefMut<'a, T>> for ElementRefMut<'a, T> {
+    fn from(element: vec_cell::ElementRefMut<'a, T>) -> Self {
+        Self { inner: RefMutInner::Cell(element) }
+    }
+}
+
+impl<T> ops::Deref for ElementRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match &self.inner {
+            RefMutInner::Cell(element) => element,
+            RefMutInner::Flag(element, _) => element,
+        }
+    }
+}
+
+impl<T> ops::DerefMut for ElementRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        match &mut self.inner {
+            RefMutInner::Cell(element) => element,
+            RefMutInner::Flag(element, _) => element,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ElementRefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+// Positive values count shared borrows, -1 marks an exclusive one.
+#[derive(Debug, Default)]
+pub(crate) struct BorrowFlag(Cell<isize>);
+
+impl BorrowFlag {
+    pub(crate) fn try_borrow(&self) -> Option<SharedFlag<'_>> {
+        let borrows = self.0.get();
+        if borrows < 0 {
+            return None;
+        }
+
+        self.0.set(borrows + 1);
+
+        Some(SharedFlag(self))
+    }
+
+    pub(crate) fn try_borrow_mut(&self) -> Option<ExclusiveFlag<'_>> {
+        if self.0.get() != 0 {
+            return None;
+        }
+
+        self.0.set(-1);
+
+        Some(ExclusiveFlag(self))
+    }
+
+    pub(crate) fn is_borrowed(&self) -> bool {
+        self.0.get() != 0
+    }
+}
+
+pub(crate) struct SharedFlag<'a>(&'a BorrowFlag);
+
+impl Drop for SharedFlag<'_> {
+    fn drop(&mut self) {
+        let flag = &self.0 .0;
+        flag.set(flag.get() - 1);
+    }
+}
+
+pub(crate) struct ExclusiveFlag<'a>(&'a BorrowFlag);
+
+impl Drop for ExclusiveFlag<'_> {
+    fn drop(&mut self) {
+        self.0 .0.set(0);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{Arena, ArenaResult, Index};
+
+use std::any::{self, Any, TypeId};
+use std::fmt;
+
+pub trait ErasedArena: Any {
+    fn len(&self) -> usize;
+    fn capacity(&self) -> usize;
+    fn contains(&self, index: Index) -> bool;
+    fn remove(&mut self, index: Index) -> ArenaResult<()>;
+
+    fn element_type_id(&self) -> TypeId;
+    fn type_name(&self) -> &'static str;
+    fn debug_element(&self, index: Index) -> Option<String>;
+
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl dyn ErasedArena {
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&Arena<T>> {
+        self.as_any().downcast_ref()
+    }
+
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut Arena<T>> {
+        self.as_any_mut().downcast_mut()
+    }
+}
+
+impl fmt::Debug for dyn ErasedArena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedArena")
+            .field("type_name", &self.type_name())
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<T: 'static> ErasedArena for Arena<T> {
+    fn len(&self) -> usize {
+        Arena::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        Arena::capacity(self)
+    }
+
+    fn contains(&self, index: Index) -> bool {
+        Arena::contains(self, index)
+    }
+
+    fn remove(&mut self, index: Index) -> ArenaResult<()> {
+        Arena::remove(self, index).map(drop)
+    }
+
+    fn element_type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn type_name(&self) -> &'static str {
+        any::type_name::<T>()
+    }
+
+    fn debug_element(&self, index: Index) -> Option<String> {
+        let debug = self.element_debug()?;
+
+        self.lookup(index).ok().map(|element| debug(&element))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -8,8 +8,42 @@ pub type ArenaResult<T> = Result<T, ArenaError>;
 pub enum ArenaError {
     #[error("failed to borrow element: {0}")]
     BorrowError(#[from] BorrowError),
+    #[error("failed to borrow arena while it's borrowed mutably")]
+    ArenaBorrowed,
     #[error("trying to use invalid Index")]
     InvalidIndexUsage,
     #[error("trying to access removed element")]
     RemovedElementAccess,
+    #[error("trying to use Index which belongs to another arena")]
+    ForeignIndex,
+    #[error("trying to use Index of removed element whose slot may be reused")]
+    StaleIndex,
+    #[error("trying to move tree node into its own subtree")]
+    TreeCycle,
+    #[error("trying to insert sibling next to root tree node")]
+    RootSibling,
+    #[error("trying to use element which isn't linked into list")]
+    UnlinkedElement,
+    #[error("trying to order graph which contains cycle")]
+    GraphCycle,
+    #[error("trying to add value which is already present in unique index `{0}`")]
+    DuplicateIndexKey(&'static str),
+    #[error("failed to borrow field which is already borrowed")]
+    FieldBorrowed,
+    #[error("failed to access arena file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("arena file has unexpected {0}: expected {1}, found {2}")]
+    PodLayoutMismatch(&'static str, u64, u64),
+    #[error("arena file has capacity {0} which doesn't fit in memory")]
+    PodCapacityOverflow(u64),
+    #[error("snapshot is corrupted: {0}")]
+    SnapshotCorrupted(&'static str),
+    #[error("no migration from version {1} of `{0}` in snapshot")]
+    UnsupportedSnapshotVersion(&'static str, u32),
+    #[error("snapshot doesn't contain arena of `{0}`")]
+    MissingSnapshotArena(&'static str),
+    #[error("failed to replay history because the arena was changed outside of it")]
+    HistoryConflict,
+    #[error("failed to apply operation which doesn't match the arena")]
+    OpLogConflict,
 }
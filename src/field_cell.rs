@@ -0,0 +1,160 @@
+use crate::element::{BorrowFlag, ExclusiveFlag, SharedFlag};
+use crate::{ArenaError, ArenaResult, ElementRef, TryClone};
+
+use std::cell::UnsafeCell;
+use std::{fmt, ops};
+
+// A field (or a group of fields) which is borrowed independently of the rest of its element, so
+// handles generated with `#[handleable(field_borrows)]` can read and write different fields of
+// the same element at once.
+pub struct FieldCell<T> {
+    flag: BorrowFlag,
+    value: UnsafeCell<T>,
+}
+
+impl<T> FieldCell<T> {
+    pub fn new(value: T) -> Self {
+        Self { flag: BorrowFlag::default(), value: UnsafeCell::new(value) }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    pub fn try_borrow(&self) -> ArenaResult<FieldRef<'_, T>> {
+        let flag = self.flag.try_borrow().ok_or(ArenaError::FieldBorrowed)?;
+
+        // SAFETY: the shared borrow flag is held by the returned reference.
+        Ok(FieldRef { value: unsafe { &*self.value.get() }, _flag: flag, _element: None })
+    }
+
+    pub fn try_borrow_mut(&self) -> ArenaResult<FieldRefMut<'_, T>> {
+        let flag = self.flag.try_borrow_mut().ok_or(ArenaError::FieldBorrowed)?;
+
+        // SAFETY: the exclusive borrow flag is held by the returned reference.
+        Ok(FieldRefMut { value: unsafe { &mut *self.value.get() }, _flag: flag, _element: None })
+    }
+}
+
+impl<T: Clone> TryClone for FieldCell<T> {
+    fn try_clone(&self) -> ArenaResult<Self> {
+        Ok(Self::new(self.try_borrow()?.clone()))
+    }
+}
+
+// Panics if the field is borrowed mutably, see `try_clone`.
+impl<T: Clone> Clone for FieldCell<T> {
+    fn clone(&self) -> Self {
+        self.try_clone().expect("failed to borrow field for cloning")
+    }
+}
+
+impl<T: Default> Default for FieldCell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for FieldCell<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for FieldCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.try_borrow() {
+            Ok(value) => f.debug_tuple("FieldCell").field(&*value).finish(),
+            Err(_) => f.write_str("FieldCell(<borrowed>)"),
+        }
+    }
+}
+
+// Field references created from an element reference keep the element borrowed too, so it can't
+// be borrowed mutably as a whole while its fields are in use.
+pub struct FieldRef<'a, T: ?Sized> {
+    value: &'a T,
+    _flag: SharedFlag<'a>,
+    _element: Option<ElementRef<'a, ()>>,
+}
+
+impl<'a, T> FieldRef<'a, T> {
+    pub fn from_element(element: ElementRef<'a, FieldCell<T>>) -> ArenaResult<Self> {
+        // SAFETY: the cell stays borrowed by the element reference, which is kept by the field
+        // reference.
+        let cell = unsafe { &*(&*element as *const FieldCell<T>) };
+
+        let mut field = cell.try_borrow()?;
+        field._element = Some(ElementRef::map(element, |_| &()));
+
+        Ok(field)
+    }
+}
+
+impl<'a, T: ?Sized> FieldRef<'a, T> {
+    pub fn map<U: ?Sized>(this: Self, f: impl FnOnce(&T) -> &U) -> FieldRef<'a, U> {
+        FieldRef { value: f(this.value), _flag: this._flag, _element: this._element }
+    }
+}
+
+impl<T: ?Sized> ops::Deref for FieldRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for FieldRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+pub struct FieldRefMut<'a, T: ?Sized> {
+    value: &'a mut T,
+    _flag: ExclusiveFlag<'a>,
+    _element: Option<ElementRef<'a, ()>>,
+}
+
+impl<'a, T> FieldRefMut<'a, T> {
+    pub fn from_element(element: ElementRef<'a, FieldCell<T>>) -> ArenaResult<Self> {
+        // SAFETY: see `FieldRef::from_element`.
+        let cell = unsafe { &*(&*element as *const FieldCell<T>) };
+
+        let mut field = cell.try_borrow_mut()?;
+        field._element = Some(ElementRef::map(element, |_| &()));
+
+        Ok(field)
+    }
+}
+
+impl<'a, T: ?Sized> FieldRefMut<'a, T> {
+    pub fn map<U: ?Sized>(this: Self, f: impl FnOnce(&mut T) -> &mut U) -> FieldRefMut<'a, U> {
+        FieldRefMut { value: f(this.value), _flag: this._flag, _element: this._element }
+    }
+}
+
+impl<T: ?Sized> ops::Deref for FieldRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: ?Sized> ops::DerefMut for FieldRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for FieldRefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{Arena, ArenaError, ArenaResult, Index, Storage};
+
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::mem;
+use std::ops::RangeBounds;
+
+pub trait ElementIndexes<T>: Send {
+    // Must leave the indexes untouched if `element` is rejected.
+    fn insert(&mut self, index: Index, element: &T) -> ArenaResult<()>;
+    fn remove(&mut self, index: Index, element: &T);
+
+    fn clone_box(&self) -> Box<dyn ElementIndexes<T>>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T> fmt::Debug for dyn ElementIndexes<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ElementIndexes")
+    }
+}
+
+pub trait Indexed: Sized {
+    type Indexes: ElementIndexes<Self> + Default + 'static;
+}
+
+// Maps which can back a `KeyIndex`. Elements with the same key are kept in a set, so adding and
+// removing them doesn't depend on how many share the key.
+pub trait IndexEntries: Default {
+    type Key: Clone;
+
+    fn entries(&self, key: &Self::Key) -> Option<&BTreeSet<Index>>;
+    fn entries_mut(&mut self, key: &Self::Key) -> Option<&mut BTreeSet<Index>>;
+    fn entries_or_default(&mut self, key: Self::Key) -> &mut BTreeSet<Index>;
+    fn remove_entries(&mut self, key: &Self::Key);
+}
+
+impl<K: Hash + Eq + Clone> IndexEntries for HashMap<K, BTreeSet<Index>> {
+    type Key = K;
+
+    fn entries(&self, key: &K) -> Option<&BTreeSet<Index>> {
+        self.get(key)
+    }
+
+    fn entries_mut(&mut self, key: &K) -> Option<&mut BTreeSet<Index>> {
+        self.get_mut(key)
+    }
+
+    fn entries_or_default(&mut self, key: K) -> &mut BTreeSet<Index> {
+        self.entry(key).or_default()
+    }
+
+    fn remove_entries(&mut self, key: &K) {
+        self.remove(key);
+    }
+}
+
+impl<K: Ord + Clone> IndexEntries for BTreeMap<K, BTreeSet<Index>> {
+    type Key = K;
+
+    fn entries(&self, key: &K) -> Option<&BTreeSet<Index>> {
+        self.get(key)
+    }
+
+    fn entries_mut(&mut self, key: &K) -> Option<&mut BTreeSet<Index>> {
+        self.get_mut(key)
+    }
+
+    fn entries_or_default(&mut self, key: K) -> &mut BTreeSet<Index> {
+        self.entry(key).or_default()
+    }
+
+    fn remove_entries(&mut self, key: &K) {
+        self.remove(key);
+    }
+}
+
+pub type HashIndex<K> = KeyIndex<HashMap<K, BTreeSet<Index>>>;
+pub type BTreeIndex<K> = KeyIndex<BTreeMap<K, BTreeSet<Index>>>;
+
+#[derive(Debug, Clone)]
+pub struct KeyIndex<M> {
+    field: &'static str,
+    unique: bool,
+    entries: M,
+}
+
+impl<M: IndexEntries> KeyIndex<M> {
+    pub fn new(field: &'static str, unique: bool) -> Self {
+        Self { field, unique, entries: M::default() }
+    }
+
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+
+    pub fn is_unique(&self) -> bool {
+        self.unique
+    }
+
+    pub fn check(&self, index: Index, key: &M::Key) -> ArenaResult<()> {
+        if self.unique && self.find_all(key).any(|other| other != index) {
+            return Err(ArenaError::DuplicateIndexKey(self.field));
+        }
+
+        Ok(())
+    }
+
+    pub fn insert(&mut self, index: Index, key: &M::Key) {
+        self.entries.entries_or_default(key.clone()).insert(index);
+    }
+
+    pub fn remove(&mut self, index: Index, key: &M::Key) {
+        let Some(indices) = self.entries.entries_mut(key) else {
+            return;
+        };
+
+        indices.remove(&index);
+        if indices.is_empty() {
+            self.entries.remove_entries(key);
+        }
+    }
+
+    pub fn replace(&mut self, index: Index, old: &M::Key, new: &M::Key) -> ArenaResult<()> {
+        self.check(index, new)?;
+        self.remove(index, old);
+        self.insert(index, new);
+
+        Ok(())
+    }
+
+    // Like the lookups without indexes, this returns the element with the lowest index.
+    pub fn find(&self, key: &M::Key) -> Option<Index> {
+        self.find_all(key).next()
+    }
+
+    pub fn find_all(&self, key: &M::Key) -> impl DoubleEndedIterator<Item = Index> + '_ {
+        self.entries.entries(key).into_iter().flatten().copied()
+    }
+}
+
+impl<K: Ord + Clone> BTreeIndex<K> {
+    // Elements with equal keys are yielded in index order.
+    pub fn range(&self, range: impl RangeBounds<K>) -> impl DoubleEndedIterator<Item = Index> + '_ {
+        self.entries.range(range).flat_map(|(_, indices)| indices.iter().copied())
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, Index)> + '_ {
+        self.entries
+            .iter()
+            .flat_map(|(key, indices)| indices.iter().map(move |index| (key, *index)))
+    }
+}
+
+impl<T, S: ?Sized + Storage<T>> Arena<T, S> {
+    // Once enabled, indexes are kept in sync by `add`, `remove` and the generated setters.
+    // Changing an indexed field through `lookup_mut` bypasses them.
+    pub fn enable_indexes(&mut self) -> ArenaResult<()>
+    where
+        T: Indexed,
+    {
+        let mut indexes = T::Indexes::default();
+        self.indices()
+            .try_for_each(|index| indexes.insert(index, &*self.lookup(index)?))?;
+
+        self.set_indexes(Box::new(indexes));
+
+        Ok(())
+    }
+
+    pub fn has_indexes(&self) -> bool {
+        self.indexes().is_some()
+    }
+
+    // Returns `None` if indexes aren't enabled or are being updated.
+    pub fn with_indexes<R>(&self, f: impl FnOnce(&T::Indexes) -> R) -> Option<R>
+    where
+        T: Indexed,
+    {
+        let indexes = self.indexes()?.try_borrow().ok()?;
+
+        indexes.as_any().downcast_ref::<T::Indexes>().map(f)
+    }
+
+    pub fn update_indexed<R>(
+        &self,
+        index: Index,
+        f: impl FnOnce(&mut T, Option<&mut T::Indexes>) -> ArenaResult<R>,
+    ) -> ArenaResult<R>
+    where
+        T: Indexed,
+    {
+        let mut element = self.lookup_mut(index)?;
+
+        match self.indexes() {
+            Some(indexes) => {
+                let mut indexes =
+                    indexes.try_borrow_mut().map_err(|_| ArenaError::ArenaBorrowed)?;
+
+                f(&mut element, indexes.as_any_mut().downcast_mut::<T::Indexes>())
+            }
+            None => f(&mut element, None),
+        }
+    }
+
+    // Reindexes the whole element, for changes whose affected fields aren't known statically. If
+    // `element` is rejected, the arena is left untouched.
+    pub(crate) fn replace_reindexed(&self, index: Index, mut element: T) -> ArenaResult<T> {
+        self.swap_reindexed(index, |current| mem::swap(current, &mut element))?;
+
+        Ok(element)
+    }
+
+    // Like `replace_reindexed`, but `swap` changes the element in place. It has to be its own
+    // inverse, since it's applied again to revert the element if its new keys are rejected.
+    pub(crate) fn swap_reindexed(
+        &self,
+        index: Index,
+        mut swap: impl FnMut(&mut T),
+    ) -> ArenaResult<()> {
+        let mut element = self.lookup_mut(index)?;
+        let Some(indexes) = self.indexes() else {
+            swap(&mut element);
+
+            return Ok(());
+        };
+        let mut indexes = indexes.try_borrow_mut().map_err(|_| ArenaError::ArenaBorrowed)?;
+
+        indexes.remove(index, &element);
+        swap(&mut element);
+        if let Err(err) = indexes.insert(index, &element) {
+            swap(&mut element);
+            // The keys of the reverted element were just removed, so they can't collide.
+            let _ = indexes.insert(index, &element);
+
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    // Fails if adding all of `elements` would violate a unique index, so adding several elements
+    // can be rejected before any of them is added.
+    pub(crate) fn check_indexes<'e>(
+        &self,
+        elements: impl IntoIterator<Item = &'e T>,
+    ) -> ArenaResult<()>
+    where
+        T: 'e,
+    {
+        let Some(indexes) = self.indexes() else {
+            return Ok(());
+        };
+        let mut indexes = indexes.try_borrow().map_err(|_| ArenaError::ArenaBorrowed)?.clone_box();
+
+        // Slots past the capacity can't collide with elements which are already indexed.
+        elements.into_iter().enumerate().try_for_each(|(offset, element)| {
+            indexes.insert(Index::from(self.capacity() + offset), element)
+        })
+    }
+
+    // Scans the whole arena, prefer ordered indexes. Predicate lookups without indexes go through
+    // `query`.
+    pub fn range_by<K: Ord>(
+        &self,
+        mut key: impl FnMut(&T) -> K,
+        range: impl RangeBounds<K>,
+    ) -> Vec<Index> {
+        let mut keyed = self
+            .indices()
+            .filter_map(|index| {
+                let key = key(&*self.lookup(index).ok()?);
+
+                range.contains(&key).then_some((key, index))
+            })
+            .collect::<Vec<_>>();
+        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        keyed.into_iter().map(|(_, index)| index).collect()
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{Arena, ArenaError, ArenaResult, AsDynArena, Handle, Handleable, Index};
+use crate::{References, Storage};
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// References to invalid, out of bounds or removed elements are not treated as edges.
+impl<T: References, S: ?Sized + Storage<T>> Arena<T, S> {
+    pub fn successors(&self, index: Index) -> ArenaResult<Vec<Index>> {
+        let mut successors = vec![];
+        self.lookup(index)?.visit_references(&mut |_, reference| {
+            if self.contains(reference) && !successors.contains(&reference) {
+                successors.push(reference);
+            }
+        });
+
+        Ok(successors)
+    }
+
+    pub fn bfs(&self, start: Index) -> ArenaResult<Vec<Index>> {
+        self.check_node(start)?;
+
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        let mut order = vec![];
+        while let Some(index) = queue.pop_front() {
+            self.successors(index)?.into_iter().for_each(|successor| {
+                if visited.insert(successor) {
+                    queue.push_back(successor);
+                }
+            });
+
+            order.push(index);
+        }
+
+        Ok(order)
+    }
+
+    // Pre-order, visiting references in the order they're reported by `visit_references`.
+    pub fn dfs(&self, start: Index) -> ArenaResult<Vec<Index>> {
+        self.check_node(start)?;
+
+        let mut visited = HashSet::new();
+        let mut stack = vec![start];
+        let mut order = vec![];
+        while let Some(index) = stack.pop() {
+            if !visited.insert(index) {
+                continue;
+            }
+
+            let successors = self.successors(index)?;
+            stack.extend(
+                successors
+                    .into_iter()
+                    .rev()
+                    .filter(|successor| !visited.contains(successor)),
+            );
+
+            order.push(index);
+        }
+
+        Ok(order)
+    }
+
+    // Every element comes before the elements it references.
+    pub fn topological_sort(&self) -> ArenaResult<Vec<Index>> {
+        let nodes = self.nodes();
+
+        let mut successors = HashMap::new();
+        let mut in_degree = nodes.iter().map(|index| (*index, 0usize)).collect::<HashMap<_, _>>();
+        nodes.iter().try_for_each(|index| {
+            let targets = self.successors(*index)?;
+            targets
+                .iter()
+                .for_each(|target| *in_degree.entry(*target).or_default() += 1);
+            successors.insert(*index, targets);
+
+            Ok::<_, ArenaError>(())
+        })?;
+
+        let mut queue = nodes
+            .iter()
+            .copied()
+            .filter(|index| in_degree[index] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = vec![];
+        while let Some(index) = queue.pop_front() {
+            successors[&index].iter().for_each(|successor| {
+                let degree = in_degree.get_mut(successor).unwrap();
+                *degree -= 1;
+
+                if *degree == 0 {
+                    queue.push_back(*successor);
+                }
+            });
+
+            order.push(index);
+        }
+
+        if order.len() != nodes.len() {
+            return Err(ArenaError::GraphCycle);
+        }
+
+        Ok(order)
+    }
+
+    // Returns elements of the first found cycle, each referencing the next and the last
+    // referencing the first.
+    pub fn find_cycle(&self) -> ArenaResult<Option<Vec<Index>>> {
+        let mut finished = HashSet::new();
+        for root in self.nodes() {
+            if finished.contains(&root) {
+                continue;
+            }
+
+            let mut path = vec![(root, self.successors(root)?, 0)];
+            while let Some((index, successors, next)) = path.last_mut() {
+                let Some(successor) = successors.get(*next).copied() else {
+                    finished.insert(*index);
+                    path.pop();
+
+                    continue;
+                };
+                *next += 1;
+
+                if let Some(start) = path.iter().position(|(index, ..)| *index == successor) {
+                    return Ok(Some(path[start..].iter().map(|(index, ..)| *index).collect()));
+                }
+
+                if !finished.contains(&successor) {
+                    path.push((successor, self.successors(successor)?, 0));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub fn has_cycle(&self) -> ArenaResult<bool> {
+        self.find_cycle().map(|cycle| cycle.is_some())
+    }
+
+    // Returns path from `from` to `to` inclusive with the least number of references followed.
+    pub fn shortest_path(&self, from: Index, to: Index) -> ArenaResult<Option<Vec<Index>>> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+
+        let mut parents = HashMap::from([(from, from)]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(index) = queue.pop_front() {
+            if index == to {
+                let mut path = vec![to];
+                let mut current = to;
+                while current != from {
+                    current = parents[&current];
+                    path.push(current);
+                }
+                path.reverse();
+
+                return Ok(Some(path));
+            }
+
+            self.successors(index)?.into_iter().for_each(|successor| {
+                if let Entry::Vacant(entry) = parents.entry(successor) {
+                    entry.insert(index);
+                    queue.push_back(successor);
+                }
+            });
+        }
+
+        Ok(None)
+    }
+
+    fn nodes(&self) -> Vec<Index> {
+        (0..self.capacity())
+            .map(Index::from)
+            .filter(|index| self.contains(*index))
+            .collect()
+    }
+
+    fn check_node(&self, index: Index) -> ArenaResult<()> {
+        if index.is_invalid() {
+            return Err(ArenaError::InvalidIndexUsage);
+        }
+
+        if !self.contains(index) {
+            return Err(ArenaError::RemovedElementAccess);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'arena, T: Handleable<'arena>, S: ?Sized + Storage<T>> Arena<T, S>
+where
+    Self: AsDynArena<'arena, T>,
+{
+    pub fn handles(
+        &'arena self,
+        indices: impl IntoIterator<Item = Index>,
+        userdata: <T::Handle as Handle<'arena>>::Userdata,
+    ) -> Vec<T::Handle> {
+        indices
+            .into_iter()
+            .map(|index| self.handle(index, userdata.clone()))
+            .collect()
+    }
+}
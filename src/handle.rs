@@ -1,12 +1,13 @@
-use crate::Arena;
 use crate::ArenaResult;
+use crate::DetachedHandle;
+use crate::DynArena;
+use crate::ElementRef;
+use crate::ElementRefMut;
 use crate::Index;
 
 use std::cmp;
 use std::fmt;
 
-use vec_cell::{ElementRef, ElementRefMut};
-
 #[derive(Debug, Clone, Copy)]
 pub enum Void {}
 
@@ -38,13 +39,17 @@ where
         self.to_raw().get().is_ok()
     }
 
-    fn arena(&self) -> &'arena Arena<Self::Type> {
+    fn arena(&self) -> &'arena DynArena<'arena, Self::Type> {
         self.to_raw().arena()
     }
 
     fn index(&self) -> Index {
         self.to_raw().index()
     }
+
+    fn detach(&self) -> ArenaResult<DetachedHandle<Self::Type>> {
+        self.arena().detach(self.index())
+    }
 }
 
 impl<'arena, H: Handle<'arena>> From<H> for RawHandle<'arena, H::Type> {
@@ -54,12 +59,12 @@ impl<'arena, H: Handle<'arena>> From<H> for RawHandle<'arena, H::Type> {
 }
 
 pub struct RawHandle<'arena, T> {
-    arena: &'arena Arena<T>,
+    arena: &'arena DynArena<'arena, T>,
     index: Index,
 }
 
 impl<'arena, T> RawHandle<'arena, T> {
-    pub(crate) fn new(arena: &'arena Arena<T>, index: Index) -> Self {
+    pub(crate) fn new(arena: &'arena DynArena<'arena, T>, index: Index) -> Self {
         Self { arena, index }
     }
 }
@@ -73,7 +78,7 @@ impl<'arena, T: Handleable<'arena>> RawHandle<'arena, T> {
         self.arena().lookup_mut(self.index())
     }
 
-    fn arena(&self) -> &'arena Arena<T> {
+    fn arena(&self) -> &'arena DynArena<'arena, T> {
         self.arena
     }
 
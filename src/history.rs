@@ -0,0 +1,263 @@
+use crate::{Arena, ArenaError, ArenaResult, Index, Storage};
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::{fmt, mem};
+
+type Change<T> = Box<dyn FnMut(&mut T) + Send>;
+
+enum Operation<T> {
+    // Swaps the recorded value with the field, so applying it again reverts the swap.
+    Change { index: Index, change: Change<T> },
+    // Inserts the held element back into its slot, or takes the element out of the arena if
+    // nothing is held.
+    Slot { index: Index, element: Option<T> },
+}
+
+struct Entry<T> {
+    name: Option<String>,
+    operations: Vec<Operation<T>>,
+}
+
+// Undo and redo stacks of an arena. Recorded operations are their own inverses, so undoing an
+// entry applies its operations in reverse order and moves it to the redo stack as is.
+pub(crate) struct History<T> {
+    undo: VecDeque<Entry<T>>,
+    redo: Vec<Entry<T>>,
+    limit: usize,
+    clone: fn(&T) -> T,
+    transaction: Option<Entry<T>>,
+    depth: usize,
+}
+
+impl<T> History<T> {
+    fn record(&mut self, operation: Operation<T>) {
+        self.redo.clear();
+
+        match &mut self.transaction {
+            Some(transaction) => transaction.operations.push(operation),
+            None => self.push(Entry { name: None, operations: vec![operation] }),
+        }
+    }
+
+    fn push(&mut self, entry: Entry<T>) {
+        if entry.operations.is_empty() {
+            return;
+        }
+
+        self.undo.push_back(entry);
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+
+    // Entries can't be undone partially, so an open transaction is committed first.
+    fn commit(&mut self) {
+        self.depth = 0;
+        if let Some(transaction) = self.transaction.take() {
+            self.push(transaction);
+        }
+    }
+}
+
+impl<T> fmt::Debug for History<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("History")
+            .field("undo", &self.undo.len())
+            .field("redo", &self.redo.len())
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl<T, S: ?Sized + Storage<T>> Arena<T, S> {
+    // Records `add`, `remove` and setters of handles generated with `#[handleable(history)]`.
+    // Only the last `limit` entries are kept.
+    pub fn enable_history(&mut self, limit: usize)
+    where
+        T: Clone,
+    {
+        *self.history_mut() = Some(RefCell::new(History {
+            undo: VecDeque::new(),
+            redo: vec![],
+            limit,
+            clone: T::clone,
+            transaction: None,
+            depth: 0,
+        }));
+    }
+
+    pub fn disable_history(&mut self) {
+        *self.history_mut() = None;
+    }
+
+    pub fn has_history(&self) -> bool {
+        self.history().is_some()
+    }
+
+    // Called by generated setters after replacing a field, `old` is restored on undo. Restoring it
+    // reindexes the element, and fails if that would violate a unique index.
+    pub fn record_change<V: Send + 'static>(
+        &self,
+        index: Index,
+        mut old: V,
+        field: fn(&mut T) -> &mut V,
+    ) where
+        T: 'static,
+    {
+        if let Some(history) = self.history() {
+            let change = Box::new(move |element: &mut T| mem::swap(field(element), &mut old));
+            history.borrow_mut().record(Operation::Change { index, change });
+        }
+    }
+
+    pub(crate) fn record_slot(&self, index: Index, removed: Option<&T>) {
+        if let Some(history) = self.history() {
+            let mut history = history.borrow_mut();
+            let element = removed.map(history.clone);
+            history.record(Operation::Slot { index, element });
+        }
+    }
+
+    // Transactions can be nested, operations recorded until the outermost one ends are undone
+    // together.
+    pub fn begin_transaction(&self, name: impl Into<String>) {
+        if let Some(history) = self.history() {
+            let mut history = history.borrow_mut();
+            if history.depth == 0 {
+                history.transaction = Some(Entry { name: Some(name.into()), operations: vec![] });
+            }
+            history.depth += 1;
+        }
+    }
+
+    pub fn end_transaction(&self) {
+        if let Some(history) = self.history() {
+            let mut history = history.borrow_mut();
+            match history.depth {
+                0 => {}
+                1 => history.commit(),
+                _ => history.depth -= 1,
+            }
+        }
+    }
+
+    pub fn transaction<R>(&mut self, name: impl Into<String>, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.begin_transaction(name);
+        let result = f(self);
+        self.end_transaction();
+
+        result
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history().is_some_and(|history| {
+            let history = history.borrow();
+
+            !history.undo.is_empty() || history.transaction.is_some()
+        })
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history().is_some_and(|history| !history.borrow().redo.is_empty())
+    }
+
+    // Operations recorded outside of transactions have no name.
+    pub fn undo_name(&self) -> Option<String> {
+        let history = self.history()?.borrow();
+
+        history.undo.back()?.name.clone()
+    }
+
+    pub fn redo_name(&self) -> Option<String> {
+        let history = self.history()?.borrow();
+
+        history.redo.last()?.name.clone()
+    }
+
+    // Returns `false` if there's nothing to undo. If an operation fails, the ones already
+    // applied are reverted and the entry stays on the undo stack.
+    pub fn undo(&mut self) -> ArenaResult<bool> {
+        self.replay(true)
+    }
+
+    pub fn redo(&mut self) -> ArenaResult<bool> {
+        self.replay(false)
+    }
+
+    fn replay(&mut self, undo: bool) -> ArenaResult<bool> {
+        // Taking the history out keeps `add` and `remove` from recording while it's replayed.
+        let Some(history) = self.history_mut().take() else {
+            return Ok(false);
+        };
+        let mut history = history.into_inner();
+        history.commit();
+
+        let entry = match undo {
+            true => history.undo.pop_back(),
+            false => history.redo.pop(),
+        };
+        let result = match entry {
+            Some(mut entry) => {
+                let result = self.apply_entry(&mut entry, undo);
+                match (undo, result.is_ok()) {
+                    (true, true) | (false, false) => history.redo.push(entry),
+                    (true, false) | (false, true) => history.undo.push_back(entry),
+                }
+
+                result.map(|_| true)
+            }
+            None => Ok(false),
+        };
+
+        *self.history_mut() = Some(RefCell::new(history));
+
+        result
+    }
+
+    fn apply_entry(&mut self, entry: &mut Entry<T>, reverse: bool) -> ArenaResult<()> {
+        let len = entry.operations.len();
+        let order = |i: usize| match reverse {
+            true => len - 1 - i,
+            false => i,
+        };
+
+        for i in 0..len {
+            if let Err(err) = self.apply_operation(&mut entry.operations[order(i)]) {
+                // Best effort, the operations were just applied so they should apply back.
+                (0..i).rev().for_each(|j| {
+                    let _ = self.apply_operation(&mut entry.operations[order(j)]);
+                });
+
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_operation(&mut self, operation: &mut Operation<T>) -> ArenaResult<()> {
+        match operation {
+            Operation::Change { index, change } => {
+                self.swap_reindexed(*index, |element| change(element))?;
+                self.log_replace(*index)?;
+            }
+            Operation::Slot { index, element } => match element.take() {
+                Some(value) => {
+                    // Replaying the history in order finds the recorded slot vacant unless the
+                    // arena was changed outside of it.
+                    if !self.is_vacant(*index) {
+                        *element = Some(value);
+
+                        return Err(ArenaError::HistoryConflict);
+                    }
+
+                    self.add_at(*index, value)?;
+                }
+                None => *element = Some(self.remove(*index)?),
+            },
+        }
+
+        Ok(())
+    }
+}
@@ -1,11 +1,63 @@
 pub mod arena;
+pub mod chunked;
+pub mod detached;
+pub mod element;
+pub mod erased;
 pub mod error;
+pub mod field_cell;
+pub mod field_index;
+pub mod graph;
 pub mod handle;
+pub mod history;
 pub mod index;
+pub mod list;
+pub mod oplog;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod pod;
+pub mod query;
+pub mod reference;
+pub mod relation;
+pub mod remap;
+pub mod shared;
+pub mod shared_handle;
+pub mod snapshot;
+pub mod soa;
+pub mod sparse_set;
+pub mod storage;
+pub mod system;
+pub mod tree;
+pub mod validate;
+pub mod world;
 
 pub use arena::*;
+pub use chunked::*;
+pub use detached::*;
+pub use element::*;
+pub use erased::*;
 pub use error::*;
+pub use field_cell::*;
+pub use field_index::*;
 pub use handle::*;
 pub use index::*;
+pub use list::*;
+pub use oplog::*;
+#[cfg(feature = "rayon")]
+pub use par::*;
+pub use pod::*;
+pub use query::*;
+pub use reference::*;
+pub use relation::*;
+pub use remap::*;
+pub use shared::*;
+pub use shared_handle::*;
+pub use snapshot::*;
+pub use soa::*;
+pub use sparse_set::*;
+pub use storage::*;
+pub use system::*;
+pub use tree::*;
+pub use validate::*;
+pub use world::*;
 
-pub use vec_cell::{BorrowError, ElementRef, ElementRefMut};
+pub use vec_cell::BorrowError;
@@ -0,0 +1,334 @@
+use crate::{Arena, ArenaError, ArenaResult, Handle, Handleable, Index};
+
+use std::marker::PhantomData;
+use std::{fmt, iter};
+
+#[derive(Debug, Clone, Copy)]
+struct ListLinks {
+    prev: Option<Index>,
+    next: Option<Index>,
+    generation: u32,
+}
+
+// Links are keyed by arena slot and remember the generation of the linked element, so entries of
+// elements removed from the arena are skipped instead of resolving to whatever reuses the slot.
+pub struct ArenaList<T> {
+    links: Vec<Option<ListLinks>>,
+    head: Option<Index>,
+    tail: Option<Index>,
+    len: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> ArenaList<T> {
+    pub fn new() -> Self {
+        Self { links: vec![], head: None, tail: None, len: 0, _marker: PhantomData }
+    }
+
+    // Includes entries of removed elements until they're pruned.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.links(index).is_some()
+    }
+
+    pub fn front(&self, arena: &Arena<T>) -> Option<Index> {
+        self.iter(arena).next()
+    }
+
+    pub fn back(&self, arena: &Arena<T>) -> Option<Index> {
+        let mut current = self.tail;
+        while let Some(index) = current {
+            let links = self.links(index)?;
+            if is_live(arena, index, links) {
+                return Some(index);
+            }
+
+            current = links.prev;
+        }
+
+        None
+    }
+
+    // Pushing an element which is already linked moves it.
+    pub fn push_front(&mut self, arena: &Arena<T>, index: Index) -> ArenaResult<()> {
+        check_element(arena, index)?;
+        self.unlink(index);
+
+        let head = self.head;
+        self.link(arena, index, None, head)
+    }
+
+    pub fn push_back(&mut self, arena: &Arena<T>, index: Index) -> ArenaResult<()> {
+        check_element(arena, index)?;
+        self.unlink(index);
+
+        let tail = self.tail;
+        self.link(arena, index, tail, None)
+    }
+
+    pub fn insert_before(
+        &mut self,
+        arena: &Arena<T>,
+        anchor: Index,
+        index: Index,
+    ) -> ArenaResult<()> {
+        self.links(anchor).ok_or(ArenaError::UnlinkedElement)?;
+        check_element(arena, index)?;
+        if anchor == index {
+            self.links_mut(index).generation = arena.generation(index);
+
+            return Ok(());
+        }
+
+        self.unlink(index);
+
+        let prev = self.links(anchor).and_then(|links| links.prev);
+        self.link(arena, index, prev, Some(anchor))
+    }
+
+    pub fn insert_after(
+        &mut self,
+        arena: &Arena<T>,
+        anchor: Index,
+        index: Index,
+    ) -> ArenaResult<()> {
+        self.links(anchor).ok_or(ArenaError::UnlinkedElement)?;
+        check_element(arena, index)?;
+        if anchor == index {
+            self.links_mut(index).generation = arena.generation(index);
+
+            return Ok(());
+        }
+
+        self.unlink(index);
+
+        let next = self.links(anchor).and_then(|links| links.next);
+        self.link(arena, index, Some(anchor), next)
+    }
+
+    pub fn pop_front(&mut self, arena: &Arena<T>) -> Option<Index> {
+        while let Some(head) = self.head {
+            let live = self.links(head).is_some_and(|links| is_live(arena, head, links));
+            self.unlink(head);
+
+            if live {
+                return Some(head);
+            }
+        }
+
+        None
+    }
+
+    pub fn pop_back(&mut self, arena: &Arena<T>) -> Option<Index> {
+        while let Some(tail) = self.tail {
+            let live = self.links(tail).is_some_and(|links| is_live(arena, tail, links));
+            self.unlink(tail);
+
+            if live {
+                return Some(tail);
+            }
+        }
+
+        None
+    }
+
+    pub fn unlink(&mut self, index: Index) -> bool {
+        let Some(ListLinks { prev, next, .. }) = self.links(index).copied() else {
+            return false;
+        };
+
+        match prev {
+            Some(prev) => self.links_mut(prev).next = next,
+            None => self.head = next,
+        }
+
+        match next {
+            Some(next) => self.links_mut(next).prev = prev,
+            None => self.tail = prev,
+        }
+
+        self.links[<Index as Into<usize>>::into(index)] = None;
+        self.len -= 1;
+
+        true
+    }
+
+    // Unlinks entries of elements which were removed from `arena`, returning their count.
+    pub fn prune(&mut self, arena: &Arena<T>) -> usize {
+        let mut pruned = 0;
+        let mut current = self.head;
+        while let Some(index) = current {
+            let links = *self.links_mut(index);
+            current = links.next;
+
+            if !is_live(arena, index, &links) {
+                self.unlink(index);
+                pruned += 1;
+            }
+        }
+
+        pruned
+    }
+
+    pub fn clear(&mut self) {
+        self.links.clear();
+        self.head = None;
+        self.tail = None;
+        self.len = 0;
+    }
+
+    pub fn iter<'list>(&'list self, arena: &'list Arena<T>) -> ListIter<'list, T> {
+        ListIter { list: self, arena, next: self.head }
+    }
+
+    fn links(&self, index: Index) -> Option<&ListLinks> {
+        if index.is_invalid() {
+            return None;
+        }
+
+        self.links
+            .get(<Index as Into<usize>>::into(index))
+            .and_then(Option::as_ref)
+    }
+
+    fn links_mut(&mut self, index: Index) -> &mut ListLinks {
+        self.links[<Index as Into<usize>>::into(index)]
+            .as_mut()
+            .expect("list links of unlinked element")
+    }
+
+    fn link(
+        &mut self,
+        arena: &Arena<T>,
+        index: Index,
+        prev: Option<Index>,
+        next: Option<Index>,
+    ) -> ArenaResult<()> {
+        check_element(arena, index)?;
+
+        let slot: usize = index.into();
+        if slot >= self.links.len() {
+            self.links.resize(slot + 1, None);
+        }
+        self.links[slot] = Some(ListLinks { prev, next, generation: arena.generation(index) });
+
+        match prev {
+            Some(prev) => self.links_mut(prev).next = Some(index),
+            None => self.head = Some(index),
+        }
+
+        match next {
+            Some(next) => self.links_mut(next).prev = Some(index),
+            None => self.tail = Some(index),
+        }
+
+        self.len += 1;
+
+        Ok(())
+    }
+}
+
+impl<'arena, T: Handleable<'arena>> ArenaList<T> {
+    pub fn handle_iter(
+        &'arena self,
+        arena: &'arena Arena<T>,
+        userdata: <T::Handle as Handle<'arena>>::Userdata,
+    ) -> ListHandleIter<'arena, T> {
+        ListHandleIter { iter: self.iter(arena), userdata }
+    }
+}
+
+fn check_element<T>(arena: &Arena<T>, index: Index) -> ArenaResult<()> {
+    if index.is_invalid() {
+        return Err(ArenaError::InvalidIndexUsage);
+    }
+
+    if !arena.contains(index) {
+        return Err(ArenaError::RemovedElementAccess);
+    }
+
+    Ok(())
+}
+
+fn is_live<T>(arena: &Arena<T>, index: Index, links: &ListLinks) -> bool {
+    arena.contains(index) && arena.generation(index) == links.generation
+}
+
+impl<T> Default for ArenaList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for ArenaList<T> {
+    fn clone(&self) -> Self {
+        Self {
+            links: self.links.clone(),
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for ArenaList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut indices = vec![];
+        let mut current = self.head;
+        while let Some(index) = current {
+            indices.push(<Index as Into<i64>>::into(index));
+            current = self.links(index).and_then(|links| links.next);
+        }
+
+        f.debug_struct("ArenaList")
+            .field("len", &self.len)
+            .field("indices", &indices)
+            .finish()
+    }
+}
+
+pub struct ListIter<'list, T> {
+    list: &'list ArenaList<T>,
+    arena: &'list Arena<T>,
+    next: Option<Index>,
+}
+
+impl<T> iter::Iterator for ListIter<'_, T> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(current) = self.next {
+            let links = self.list.links(current)?;
+            self.next = links.next;
+
+            if is_live(self.arena, current, links) {
+                return Some(current);
+            }
+        }
+
+        None
+    }
+}
+
+pub struct ListHandleIter<'arena, T: Handleable<'arena>> {
+    iter: ListIter<'arena, T>,
+    userdata: <T::Handle as Handle<'arena>>::Userdata,
+}
+
+impl<'arena, T: Handleable<'arena>> iter::Iterator for ListHandleIter<'arena, T> {
+    type Item = T::Handle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next()?;
+
+        Some(self.iter.arena.handle(index, self.userdata.clone()))
+    }
+}
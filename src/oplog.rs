@@ -0,0 +1,196 @@
+use crate::{Arena, ArenaError, ArenaResult, Index, Storage};
+use crate::{SnapshotReader, SnapshotValue, SnapshotWriter};
+
+use std::fmt;
+
+// Changes of an arena in the order they happened. Elements and field values are encoded as
+// snapshot values, and ops themselves can be written with `SnapshotWriter` to send them to
+// another process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArenaOp {
+    Add { index: Index, element: Vec<u8> },
+    Remove { index: Index },
+    Set { index: Index, field: String, value: Vec<u8> },
+    // Emitted when a whole element changes at once, e.g. when a field change is undone.
+    Replace { index: Index, element: Vec<u8> },
+}
+
+impl ArenaOp {
+    pub fn index(&self) -> Index {
+        match self {
+            ArenaOp::Add { index, .. }
+            | ArenaOp::Remove { index }
+            | ArenaOp::Set { index, .. }
+            | ArenaOp::Replace { index, .. } => *index,
+        }
+    }
+}
+
+impl SnapshotValue for ArenaOp {
+    fn encode(&self, writer: &mut SnapshotWriter) {
+        match self {
+            ArenaOp::Add { index, element } => {
+                writer.write(&0u8);
+                writer.write(index);
+                writer.write_bytes(element);
+            }
+            ArenaOp::Remove { index } => {
+                writer.write(&1u8);
+                writer.write(index);
+            }
+            ArenaOp::Set { index, field, value } => {
+                writer.write(&2u8);
+                writer.write(index);
+                writer.write(field);
+                writer.write_bytes(value);
+            }
+            ArenaOp::Replace { index, element } => {
+                writer.write(&3u8);
+                writer.write(index);
+                writer.write_bytes(element);
+            }
+        }
+    }
+
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+        let tag = reader.read::<u8>()?;
+        let index = reader.read()?;
+
+        match tag {
+            0 => Ok(ArenaOp::Add { index, element: reader.read_bytes()?.to_vec() }),
+            1 => Ok(ArenaOp::Remove { index }),
+            2 => Ok(ArenaOp::Set {
+                index,
+                field: reader.read()?,
+                value: reader.read_bytes()?.to_vec(),
+            }),
+            3 => Ok(ArenaOp::Replace { index, element: reader.read_bytes()?.to_vec() }),
+            _ => Err(ArenaError::SnapshotCorrupted("unknown arena operation")),
+        }
+    }
+}
+
+// Elements which can be changed by `ArenaOp::Set`, implemented by `#[handleable(oplog)]`.
+pub trait Replicate: SnapshotValue {
+    fn apply_field(&mut self, field: &str, reader: &mut SnapshotReader<'_>) -> ArenaResult<()>;
+}
+
+pub(crate) struct OpLog<T> {
+    sink: Box<dyn FnMut(ArenaOp) + Send>,
+    encode: fn(&T, &mut SnapshotWriter),
+}
+
+impl<T> OpLog<T> {
+    fn encode(&self, element: &T) -> Vec<u8> {
+        let mut writer = SnapshotWriter::new();
+        (self.encode)(element, &mut writer);
+
+        writer.into_bytes()
+    }
+}
+
+impl<T> fmt::Debug for OpLog<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OpLog")
+    }
+}
+
+impl<T, S: ?Sized + Storage<T>> Arena<T, S> {
+    // Emits `add`, `remove` and setters of handles generated with `#[handleable(oplog)]` to
+    // `sink`, e.g. the sending half of a channel.
+    pub fn enable_op_log(&mut self, sink: impl FnMut(ArenaOp) + Send + 'static)
+    where
+        T: SnapshotValue,
+    {
+        *self.op_log_mut() = Some(OpLog { sink: Box::new(sink), encode: T::encode }.into());
+    }
+
+    pub fn disable_op_log(&mut self) {
+        *self.op_log_mut() = None;
+    }
+
+    pub fn has_op_log(&self) -> bool {
+        self.op_log().is_some()
+    }
+
+    // Called by generated setters before a field is assigned.
+    pub fn record_set<V: SnapshotValue>(&self, index: Index, field: &str, value: &V) {
+        if let Some(op_log) = self.op_log() {
+            let mut writer = SnapshotWriter::new();
+            writer.write(value);
+
+            let op = ArenaOp::Set { index, field: field.to_string(), value: writer.into_bytes() };
+            (op_log.borrow_mut().sink)(op);
+        }
+    }
+
+    pub(crate) fn log_add(&self, index: Index, element: &T) {
+        if let Some(op_log) = self.op_log() {
+            let mut op_log = op_log.borrow_mut();
+            let element = op_log.encode(element);
+            (op_log.sink)(ArenaOp::Add { index, element });
+        }
+    }
+
+    pub(crate) fn log_remove(&mut self, index: Index) {
+        if let Some(op_log) = self.op_log_mut() {
+            (op_log.get_mut().sink)(ArenaOp::Remove { index });
+        }
+    }
+
+    // Fails only if the element is borrowed, which can't happen while the arena is borrowed
+    // mutably.
+    pub(crate) fn log_replace(&mut self, index: Index) -> ArenaResult<()> {
+        if let Some(op_log) = self.op_log() {
+            let element = op_log.borrow().encode(&*self.lookup(index)?);
+            (op_log.borrow_mut().sink)(ArenaOp::Replace { index, element });
+        }
+
+        Ok(())
+    }
+
+    // Replays an op emitted by another arena. Starting from an empty arena, or a copy of the
+    // emitting arena, and applying all of its ops in order produces the same elements at the same
+    // indices. Applied ops are emitted to this arena's own log too.
+    pub fn apply(&mut self, op: &ArenaOp) -> ArenaResult<()>
+    where
+        T: Replicate,
+    {
+        match op {
+            ArenaOp::Add { index, element } => {
+                if index.is_invalid() {
+                    return Err(ArenaError::InvalidIndexUsage);
+                }
+
+                // Elements appended by `ChunkedStorage::push` can skip vacant slots, so the slot
+                // isn't necessarily the next one.
+                if !self.is_vacant(*index) {
+                    return Err(ArenaError::OpLogConflict);
+                }
+
+                self.add_at(*index, SnapshotReader::new(element).read()?)?;
+            }
+            ArenaOp::Remove { index } => {
+                self.remove(*index)?;
+            }
+            ArenaOp::Set { index, field, value } => {
+                // The field is set on a copy, so a rejected value leaves the element as it was.
+                let mut writer = SnapshotWriter::new();
+                writer.write(&*self.lookup(*index)?);
+                let mut element = SnapshotReader::new(&writer.into_bytes()).read::<T>()?;
+                element.apply_field(field, &mut SnapshotReader::new(value))?;
+
+                self.replace_reindexed(*index, element)?;
+                if let Some(op_log) = self.op_log_mut() {
+                    (op_log.get_mut().sink)(op.clone());
+                }
+            }
+            ArenaOp::Replace { index, element } => {
+                self.replace_reindexed(*index, SnapshotReader::new(element).read()?)?;
+                self.log_replace(*index)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{Arena, ElementRef, Storage};
+
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, Map, ParallelIterator};
+use rayon::{slice, vec};
+
+type ElementsIter<'r, 'arena, T> = Map<slice::Iter<'r, &'arena T>, fn(&'r &'arena T) -> &'r T>;
+
+// Holds shared borrows of all live elements, so they can't be borrowed mutably while the guard is
+// iterated in parallel, e.g. `arena.par_iter().iter().for_each(...)`. Elements which were borrowed
+// mutably when the guard was created are skipped. Iterated elements borrow the guard rather than
+// the arena, so they can't outlive the borrow flags.
+pub struct ParIter<'arena, T> {
+    elements: Vec<&'arena T>,
+    _guards: Vec<ElementRef<'arena, T>>,
+}
+
+impl<'arena, T: Sync> ParIter<'arena, T> {
+    pub fn iter(&self) -> ElementsIter<'_, 'arena, T> {
+        self.elements.par_iter().map(|element| *element)
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+impl<'r, 'arena, T: Sync> IntoParallelIterator for &'r ParIter<'arena, T> {
+    type Iter = ElementsIter<'r, 'arena, T>;
+    type Item = &'r T;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.iter()
+    }
+}
+
+impl<T, S: ?Sized + Storage<T>> Arena<T, S> {
+    pub fn par_iter(&self) -> ParIter<'_, T>
+    where
+        T: Sync,
+    {
+        let guards = self
+            .indices()
+            .filter_map(|index| self.lookup(index).ok())
+            .collect::<Vec<_>>();
+        // SAFETY: every element stays borrowed by its guard for as long as `ParIter` is alive.
+        let elements = guards
+            .iter()
+            .map(|element| unsafe { &*(&**element as *const T) })
+            .collect();
+
+        ParIter { elements, _guards: guards }
+    }
+
+    // The exclusive borrow of the arena rules out any other access, so elements are handed out
+    // without keeping borrow flags.
+    pub fn par_iter_mut(&mut self) -> vec::IntoIter<&mut T>
+    where
+        T: Send,
+    {
+        let elements = self
+            .indices()
+            .filter_map(|index| {
+                let mut element = self.lookup_mut(index).ok()?;
+
+                Some(&mut *element as *mut T)
+            })
+            .collect::<Vec<_>>();
+
+        // SAFETY: pointers are unique per slot, and slots can't move or be accessed through the
+        // arena while it's borrowed mutably.
+        elements
+            .into_iter()
+            .map(|element| unsafe { &mut *element })
+            .collect::<Vec<_>>()
+            .into_par_iter()
+    }
+}
@@ -0,0 +1,421 @@
+use crate::{Arena, ArenaError, ArenaResult, DenseStorage, Index, Storage};
+
+use std::alloc::{self, Layout};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::{fmt, mem, ptr, slice};
+
+// Types which can be written to a file as raw bytes and viewed in place after reading them back:
+// no padding, no pointers or references, and every bit pattern is a valid value.
+//
+// # Safety
+// Implementors have to uphold the requirements above.
+#[allow(clippy::missing_safety_doc)]
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($ty:ty),*) => {
+        $( unsafe impl Pod for $ty {} )*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, Index);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+// Implements `Pod` for a struct whose fields are all `Pod`. Every field has to be listed, and the
+// struct is rejected at compile time if it has padding, e.g.
+// `impl_pod_struct!(Vertex { position: [f32; 3], next: Index })` fails because of the 4 bytes
+// between `position` and `next`.
+#[macro_export]
+macro_rules! impl_pod_struct {
+    ($ty:ident { $( $field:ident : $field_ty:ty ),* $(,)? }) => {
+        const _: () = {
+            #[allow(dead_code)]
+            fn fields_are_pod(value: $ty) {
+                fn is_pod<T: $crate::Pod>(_: T) {}
+
+                let $ty { $( $field ),* } = value;
+                $( is_pod::<$field_ty>($field); )*
+            }
+
+            assert!(
+                ::std::mem::size_of::<$ty>() == 0 $( + ::std::mem::size_of::<$field_ty>() )*,
+                concat!("`", stringify!($ty), "` has padding and can't be `Pod`"),
+            );
+        };
+
+        // SAFETY: all fields are `Pod`, and their sizes add up to the size of the struct, so
+        // there's no padding.
+        unsafe impl $crate::Pod for $ty {}
+    };
+}
+
+const MAGIC: [u8; 8] = *b"ARENASYS";
+const BYTE_ORDER: u32 = 0x01020304;
+
+pub const POD_FORMAT_VERSION: u32 = 1;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct PodHeader {
+    magic: [u8; 8],
+    version: u32,
+    byte_order: u32,
+    element_size: u32,
+    element_align: u32,
+    capacity: u64,
+    len: u64,
+}
+
+// The file starts with the header, followed by the generation and the occupancy byte of every
+// slot. Elements come last, aligned to the element alignment, with vacant slots zeroed.
+struct PodLayout {
+    generations: usize,
+    occupied: usize,
+    elements: usize,
+    size: usize,
+}
+
+impl PodLayout {
+    // The capacity comes from the file header, so the offsets are checked for overflow.
+    fn new<T>(capacity: u64) -> ArenaResult<Self> {
+        let layout = usize::try_from(capacity).ok().and_then(|capacity| {
+            let generations = mem::size_of::<PodHeader>();
+            let occupied = generations.checked_add(capacity.checked_mul(mem::size_of::<u32>())?)?;
+            let elements = occupied
+                .checked_add(capacity)?
+                .checked_next_multiple_of(Self::align::<T>())?;
+            let size = elements.checked_add(capacity.checked_mul(mem::size_of::<T>())?)?;
+
+            (size <= isize::MAX as usize).then_some(Self { generations, occupied, elements, size })
+        });
+
+        layout.ok_or(ArenaError::PodCapacityOverflow(capacity))
+    }
+
+    fn align<T>() -> usize {
+        mem::align_of::<T>().max(mem::align_of::<PodHeader>())
+    }
+}
+
+impl<T: Pod, S: ?Sized + Storage<T>> Arena<T, S> {
+    // Fails if an element is borrowed mutably.
+    pub fn write_pod(&self, writer: &mut impl Write) -> ArenaResult<()> {
+        let capacity = self.capacity();
+        let layout = PodLayout::new::<T>(capacity as u64)?;
+
+        let header = PodHeader {
+            magic: MAGIC,
+            version: POD_FORMAT_VERSION,
+            byte_order: BYTE_ORDER,
+            element_size: mem::size_of::<T>() as u32,
+            element_align: mem::align_of::<T>() as u32,
+            capacity: capacity as u64,
+            len: self.len() as u64,
+        };
+        writer.write_all(bytes_of(&header))?;
+
+        (0..capacity).try_for_each(|slot| {
+            writer.write_all(&self.storage().generation(slot).to_ne_bytes())
+        })?;
+        (0..capacity)
+            .try_for_each(|slot| writer.write_all(&[self.storage().is_occupied(slot) as u8]))?;
+        writer.write_all(&vec![0; layout.elements - layout.occupied - capacity])?;
+
+        let vacant = vec![0; mem::size_of::<T>()];
+        (0..capacity).try_for_each(|slot| {
+            match self.storage().is_occupied(slot) {
+                true => writer.write_all(bytes_of(&*self.lookup(Index::from(slot))?))?,
+                false => writer.write_all(&vacant)?,
+            }
+
+            Ok::<_, ArenaError>(())
+        })
+    }
+
+    pub fn save_pod(&self, path: impl AsRef<Path>) -> ArenaResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_pod(&mut writer)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+enum PodBytes {
+    Buffer {
+        ptr: ptr::NonNull<u8>,
+        layout: Layout,
+    },
+    #[cfg(feature = "memmap2")]
+    Mapped(memmap2::Mmap),
+}
+
+impl PodBytes {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            // SAFETY: the buffer is allocated with `layout` and fully initialized by `read`.
+            PodBytes::Buffer { ptr, layout } => unsafe {
+                slice::from_raw_parts(ptr.as_ptr(), layout.size())
+            },
+            #[cfg(feature = "memmap2")]
+            PodBytes::Mapped(map) => map,
+        }
+    }
+
+    // Reads into a buffer which is aligned for the elements. The data is read before the buffer
+    // is allocated, so a bogus `size` fails once the reader runs out instead of allocating it.
+    fn read<T>(reader: &mut impl Read, size: usize) -> ArenaResult<Self> {
+        let mut data = vec![];
+        reader.take(size as u64).read_to_end(&mut data)?;
+        if data.len() != size {
+            return Err(ArenaError::PodLayoutMismatch("file size", size as u64, data.len() as u64));
+        }
+
+        let layout = Layout::from_size_align(size.max(1), PodLayout::align::<T>())
+            .map_err(|_| ArenaError::PodCapacityOverflow(size as u64))?;
+
+        // SAFETY: the layout has a non-zero size.
+        let ptr = ptr::NonNull::new(unsafe { alloc::alloc(layout) })
+            .unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        // SAFETY: the buffer has room for `size` bytes and doesn't overlap `data`.
+        unsafe { ptr::copy_nonoverlapping(data.as_ptr(), ptr.as_ptr(), size) };
+
+        Ok(PodBytes::Buffer { ptr, layout })
+    }
+}
+
+// The buffer is owned and never written after reading.
+unsafe impl Send for PodBytes {}
+unsafe impl Sync for PodBytes {}
+
+impl Drop for PodBytes {
+    fn drop(&mut self) {
+        match self {
+            // SAFETY: the buffer was allocated with the same layout.
+            PodBytes::Buffer { ptr, layout } => unsafe { alloc::dealloc(ptr.as_ptr(), *layout) },
+            #[cfg(feature = "memmap2")]
+            PodBytes::Mapped(_) => {}
+        }
+    }
+}
+
+// A read-only arena whose elements are viewed in place in the bytes of a file written by
+// `Arena::write_pod`, so opening it doesn't deserialize elements one by one. Indices and
+// generations are the same as in the written arena.
+pub struct PodArena<T: Pod> {
+    bytes: PodBytes,
+    capacity: usize,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Pod> PodArena<T> {
+    // Maps the file into memory with the `memmap2` feature and reads it into a buffer otherwise,
+    // see `read` for a safe alternative.
+    //
+    // # Safety
+    // The file must not be truncated or modified, by this or any other process, while the arena
+    // is alive.
+    #[allow(clippy::missing_safety_doc)]
+    pub unsafe fn open(path: impl AsRef<Path>) -> ArenaResult<Self> {
+        #[cfg(feature = "memmap2")]
+        {
+            let file = File::open(path)?;
+            // SAFETY: the file isn't modified while it's mapped, as required by the caller.
+            let map = unsafe { memmap2::Mmap::map(&file)? };
+
+            Self::from_bytes(PodBytes::Mapped(map))
+        }
+
+        #[cfg(not(feature = "memmap2"))]
+        Self::read(path)
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> ArenaResult<Self> {
+        let mut file = File::open(path)?;
+        let size = file.metadata()?.len() as usize;
+
+        Self::from_bytes(PodBytes::read::<T>(&mut file, size)?)
+    }
+
+    pub fn read_from(reader: &mut impl Read) -> ArenaResult<Self> {
+        let mut header_bytes = [0; mem::size_of::<PodHeader>()];
+        reader.read_exact(&mut header_bytes)?;
+        // SAFETY: the header is plain old data and is read without alignment requirements.
+        let header = unsafe { ptr::read_unaligned(header_bytes.as_ptr() as *const PodHeader) };
+        Self::validate_header(&header)?;
+
+        let size = PodLayout::new::<T>(header.capacity)?.size;
+        let bytes = PodBytes::read::<T>(&mut (&header_bytes[..]).chain(reader), size)?;
+
+        Self::from_bytes(bytes)
+    }
+
+    fn validate_header(header: &PodHeader) -> ArenaResult<()> {
+        let checks = [
+            ("magic", u64::from_ne_bytes(MAGIC), u64::from_ne_bytes(header.magic)),
+            ("version", POD_FORMAT_VERSION as u64, header.version as u64),
+            ("byte order", BYTE_ORDER as u64, header.byte_order as u64),
+            ("element size", mem::size_of::<T>() as u64, header.element_size as u64),
+            ("element alignment", mem::align_of::<T>() as u64, header.element_align as u64),
+        ];
+
+        checks
+            .into_iter()
+            .try_for_each(|(field, expected, found)| match expected == found {
+                true => Ok(()),
+                false => Err(ArenaError::PodLayoutMismatch(field, expected, found)),
+            })
+    }
+
+    fn from_bytes(bytes: PodBytes) -> ArenaResult<Self> {
+        let slice = bytes.as_slice();
+        if slice.len() < mem::size_of::<PodHeader>() {
+            return Err(ArenaError::PodLayoutMismatch(
+                "file size",
+                mem::size_of::<PodHeader>() as u64,
+                slice.len() as u64,
+            ));
+        }
+
+        // SAFETY: the header is plain old data and is read without alignment requirements.
+        let header = unsafe { ptr::read_unaligned(slice.as_ptr() as *const PodHeader) };
+        Self::validate_header(&header)?;
+
+        let layout = PodLayout::new::<T>(header.capacity)?;
+        let capacity = header.capacity as usize;
+        if slice.len() != layout.size {
+            return Err(ArenaError::PodLayoutMismatch(
+                "file size",
+                layout.size as u64,
+                slice.len() as u64,
+            ));
+        }
+
+        let address = slice.as_ptr() as usize;
+        if !address.is_multiple_of(PodLayout::align::<T>()) {
+            return Err(ArenaError::PodLayoutMismatch(
+                "data alignment",
+                PodLayout::align::<T>() as u64,
+                (address % PodLayout::align::<T>()) as u64,
+            ));
+        }
+
+        let arena = Self { bytes, capacity, len: header.len as usize, _marker: PhantomData };
+        let len = arena.occupied().iter().filter(|&&occupied| occupied != 0).count();
+        if len != arena.len {
+            return Err(ArenaError::PodLayoutMismatch("len", arena.len as u64, len as u64));
+        }
+
+        Ok(arena)
+    }
+
+    // The layout was validated on open, so it can't overflow.
+    fn layout(&self) -> PodLayout {
+        PodLayout::new::<T>(self.capacity as u64).unwrap()
+    }
+
+    fn occupied(&self) -> &[u8] {
+        let layout = self.layout();
+
+        &self.bytes.as_slice()[layout.occupied..layout.occupied + self.capacity]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        if index.is_invalid() {
+            return false;
+        }
+
+        self.occupied()
+            .get(<Index as Into<usize>>::into(index))
+            .is_some_and(|&o| o != 0)
+    }
+
+    pub fn generation(&self, index: Index) -> Option<u32> {
+        if index.is_invalid() || <Index as Into<usize>>::into(index) >= self.capacity {
+            return None;
+        }
+
+        let slot: usize = index.into();
+        let offset = self.layout().generations + slot * mem::size_of::<u32>();
+        let bytes = &self.bytes.as_slice()[offset..offset + mem::size_of::<u32>()];
+
+        Some(u32::from_ne_bytes(bytes.try_into().unwrap()))
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        let offset = self.layout().elements;
+
+        // SAFETY: the size and alignment of the elements were validated on open, and any bit
+        // pattern is a valid `Pod` value.
+        unsafe {
+            slice::from_raw_parts(
+                self.bytes.as_slice()[offset..].as_ptr() as *const T,
+                self.capacity,
+            )
+        }
+    }
+
+    pub fn get(&self, index: Index) -> ArenaResult<&T> {
+        if index.is_invalid() {
+            return Err(ArenaError::InvalidIndexUsage);
+        }
+
+        if !self.contains(index) {
+            return Err(ArenaError::RemovedElementAccess);
+        }
+
+        Ok(&self.as_slice()[<Index as Into<usize>>::into(index)])
+    }
+
+    pub fn indices(&self) -> impl Iterator<Item = Index> + '_ {
+        (0..self.capacity).map(Index::from).filter(|&index| self.contains(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> + '_ {
+        self.indices()
+            .map(|index| (index, &self.as_slice()[<Index as Into<usize>>::into(index)]))
+    }
+
+    // Copies the elements into a regular arena with the same indices. Files don't record the order
+    // in which vacant slots are reused, so the copy reuses higher slots first.
+    pub fn to_arena(&self) -> Arena<T> {
+        let slots = (0..self.capacity)
+            .map(Index::from)
+            .map(|index| self.get(index).ok().copied())
+            .collect::<Vec<_>>();
+        let generations = (0..self.capacity)
+            .map(|slot| self.generation(Index::from(slot)).unwrap())
+            .collect();
+        let vacant = (0..self.capacity).rev().filter(|slot| slots[*slot].is_none()).collect();
+
+        Arena::with_storage(DenseStorage::from_slots(slots, generations, vacant))
+    }
+}
+
+impl<T: Pod + fmt::Debug> fmt::Debug for PodArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+fn bytes_of<T: Copy>(value: &T) -> &[u8] {
+    // SAFETY: `PodHeader` and `Pod` types have no padding, so all of their bytes are initialized.
+    unsafe { slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>()) }
+}
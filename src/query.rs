@@ -0,0 +1,108 @@
+use crate::{Arena, AsDynArena, DefaultStorage, Handle, Handleable, Index, Storage};
+
+use std::iter;
+
+type Filter<'arena, T> = Box<dyn FnMut(&T) -> bool + 'arena>;
+
+// Vacant slots and elements which are mutably borrowed at the time of the test are skipped.
+pub struct Query<'arena, T, S: ?Sized = DefaultStorage<T>> {
+    arena: &'arena Arena<T, S>,
+    filters: Vec<Filter<'arena, T>>,
+}
+
+impl<'arena, T, S: ?Sized + Storage<T>> Query<'arena, T, S> {
+    pub fn filter(mut self, predicate: impl FnMut(&T) -> bool + 'arena) -> Self {
+        self.filters.push(Box::new(predicate));
+
+        self
+    }
+
+    pub fn indices(self) -> QueryIter<'arena, T, S> {
+        QueryIter { slots: self.arena.storage().occupied(), query: self }
+    }
+
+    pub fn first(self) -> Option<Index> {
+        self.indices().next()
+    }
+
+    pub fn find(self, predicate: impl FnMut(&T) -> bool + 'arena) -> Option<Index> {
+        self.filter(predicate).first()
+    }
+
+    pub fn any(self, predicate: impl FnMut(&T) -> bool + 'arena) -> bool {
+        self.find(predicate).is_some()
+    }
+
+    pub fn count(self) -> usize {
+        self.indices().count()
+    }
+
+    fn test(&mut self, index: Index) -> bool {
+        let Ok(element) = self.arena.lookup(index) else {
+            return false;
+        };
+
+        self.filters.iter_mut().all(|filter| filter(&element))
+    }
+}
+
+impl<'arena, T: Handleable<'arena>, S: ?Sized + Storage<T>> Query<'arena, T, S>
+where
+    Arena<T, S>: AsDynArena<'arena, T>,
+{
+    pub fn map_handles(
+        self,
+        userdata: <T::Handle as Handle<'arena>>::Userdata,
+    ) -> QueryHandleIter<'arena, T, S> {
+        QueryHandleIter { iter: self.indices(), userdata }
+    }
+}
+
+impl<'arena, T, S: ?Sized + Storage<T>> iter::IntoIterator for Query<'arena, T, S> {
+    type Item = Index;
+    type IntoIter = QueryIter<'arena, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.indices()
+    }
+}
+
+impl<T, S: ?Sized + Storage<T>> Arena<T, S> {
+    pub fn query(&self) -> Query<'_, T, S> {
+        Query { arena: self, filters: vec![] }
+    }
+}
+
+pub struct QueryIter<'arena, T, S: ?Sized = DefaultStorage<T>> {
+    query: Query<'arena, T, S>,
+    slots: Box<dyn Iterator<Item = usize> + 'arena>,
+}
+
+impl<T, S: ?Sized + Storage<T>> iter::Iterator for QueryIter<'_, T, S> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let query = &mut self.query;
+
+        self.slots.by_ref().map(Index::from).find(|index| query.test(*index))
+    }
+}
+
+pub struct QueryHandleIter<'arena, T: Handleable<'arena>, S: ?Sized = DefaultStorage<T>> {
+    iter: QueryIter<'arena, T, S>,
+    userdata: <T::Handle as Handle<'arena>>::Userdata,
+}
+
+impl<'arena, T: Handleable<'arena>, S: ?Sized + Storage<T>> iter::Iterator
+    for QueryHandleIter<'arena, T, S>
+where
+    Arena<T, S>: AsDynArena<'arena, T>,
+{
+    type Item = T::Handle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next()?;
+
+        Some(self.iter.query.arena.handle(index, self.userdata.clone()))
+    }
+}
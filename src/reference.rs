@@ -0,0 +1,55 @@
+use crate::Index;
+
+pub trait References<Target = Self> {
+    fn visit_references(&self, visitor: &mut dyn FnMut(&'static str, Index));
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&'static str, &mut Index));
+}
+
+pub trait ReferenceField {
+    fn visit(&self, visitor: &mut dyn FnMut(Index));
+    fn visit_mut(&mut self, visitor: &mut dyn FnMut(&mut Index));
+}
+
+impl ReferenceField for Index {
+    fn visit(&self, visitor: &mut dyn FnMut(Index)) {
+        visitor(*self);
+    }
+
+    fn visit_mut(&mut self, visitor: &mut dyn FnMut(&mut Index)) {
+        visitor(self);
+    }
+}
+
+impl ReferenceField for Option<Index> {
+    fn visit(&self, visitor: &mut dyn FnMut(Index)) {
+        if let Some(index) = self {
+            visitor(*index);
+        }
+    }
+
+    fn visit_mut(&mut self, visitor: &mut dyn FnMut(&mut Index)) {
+        if let Some(index) = self {
+            visitor(index);
+        }
+    }
+}
+
+impl ReferenceField for Vec<Index> {
+    fn visit(&self, visitor: &mut dyn FnMut(Index)) {
+        self.iter().for_each(|index| visitor(*index));
+    }
+
+    fn visit_mut(&mut self, visitor: &mut dyn FnMut(&mut Index)) {
+        self.iter_mut().for_each(visitor);
+    }
+}
+
+impl<const N: usize> ReferenceField for [Index; N] {
+    fn visit(&self, visitor: &mut dyn FnMut(Index)) {
+        self.iter().for_each(|index| visitor(*index));
+    }
+
+    fn visit_mut(&mut self, visitor: &mut dyn FnMut(&mut Index)) {
+        self.iter_mut().for_each(visitor);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{Arena, DetachedHandle, Handle, Handleable, Index, Storage, World, WorldArena};
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::{fmt, iter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationKind {
+    OneToMany,
+    ManyToMany,
+}
+
+// Links are kept by detached handles, so links of an element which was removed straight from its
+// arena, rather than through `World::remove`, never apply to an element which reuses its slot.
+// Only `World::remove` drops the links of removed elements, others stay until `prune`.
+pub struct Relation<A, B> {
+    kind: RelationKind,
+    targets: HashMap<DetachedHandle<A>, Vec<DetachedHandle<B>>>,
+    sources: HashMap<DetachedHandle<B>, Vec<DetachedHandle<A>>>,
+    _marker: PhantomData<fn() -> (A, B)>,
+}
+
+impl<A, B> Relation<A, B> {
+    pub fn new(kind: RelationKind) -> Self {
+        Self { kind, targets: HashMap::new(), sources: HashMap::new(), _marker: PhantomData }
+    }
+
+    pub fn one_to_many() -> Self {
+        Self::new(RelationKind::OneToMany)
+    }
+
+    pub fn many_to_many() -> Self {
+        Self::new(RelationKind::ManyToMany)
+    }
+
+    pub fn kind(&self) -> RelationKind {
+        self.kind
+    }
+
+    // In one-to-many relations the previous source of `target` is unlinked.
+    pub fn link(&mut self, source: DetachedHandle<A>, target: DetachedHandle<B>) {
+        if self.is_linked(source, target) {
+            return;
+        }
+
+        if self.kind == RelationKind::OneToMany {
+            if let Some(old_source) = self.source(target) {
+                self.unlink(old_source, target);
+            }
+        }
+
+        self.targets.entry(source).or_default().push(target);
+        self.sources.entry(target).or_default().push(source);
+    }
+
+    pub fn unlink(&mut self, source: DetachedHandle<A>, target: DetachedHandle<B>) -> bool {
+        let unlinked = detach_from(&mut self.targets, source, target);
+        detach_from(&mut self.sources, target, source);
+
+        unlinked
+    }
+
+    pub fn is_linked(&self, source: DetachedHandle<A>, target: DetachedHandle<B>) -> bool {
+        self.targets(source).contains(&target)
+    }
+
+    pub fn targets(&self, source: DetachedHandle<A>) -> &[DetachedHandle<B>] {
+        self.targets.get(&source).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn sources(&self, target: DetachedHandle<B>) -> &[DetachedHandle<A>] {
+        self.sources.get(&target).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn target(&self, source: DetachedHandle<A>) -> Option<DetachedHandle<B>> {
+        self.targets(source).first().copied()
+    }
+
+    pub fn source(&self, target: DetachedHandle<B>) -> Option<DetachedHandle<A>> {
+        self.sources(target).first().copied()
+    }
+
+    pub fn remove_source(&mut self, source: DetachedHandle<A>) {
+        self.targets.remove(&source).into_iter().flatten().for_each(|target| {
+            detach_from(&mut self.sources, target, source);
+        });
+    }
+
+    pub fn remove_target(&mut self, target: DetachedHandle<B>) {
+        self.sources.remove(&target).into_iter().flatten().for_each(|source| {
+            detach_from(&mut self.targets, source, target);
+        });
+    }
+
+    // Drops the links of elements which are no longer in `sources` or `targets`.
+    pub fn prune<SA, SB>(&mut self, sources: &Arena<A, SA>, targets: &Arena<B, SB>)
+    where
+        SA: ?Sized + Storage<A>,
+        SB: ?Sized + Storage<B>,
+    {
+        let removed_sources = self
+            .targets
+            .keys()
+            .filter(|source| !source.is_live(sources))
+            .copied()
+            .collect::<Vec<_>>();
+        removed_sources.into_iter().for_each(|source| self.remove_source(source));
+
+        let removed_targets = self
+            .sources
+            .keys()
+            .filter(|target| !target.is_live(targets))
+            .copied()
+            .collect::<Vec<_>>();
+        removed_targets.into_iter().for_each(|target| self.remove_target(target));
+    }
+
+    // Linked elements which were removed straight from their arena are skipped.
+    pub fn target_handles<'arena, W>(&self, world: &'arena W, source: Index) -> Vec<B::Handle>
+    where
+        W: World + WorldArena<A> + WorldArena<B>,
+        B: Handleable<'arena> + 'arena,
+        B::Handle: Handle<'arena, Userdata = &'arena W>,
+    {
+        let Ok(source) = world.arena::<A>().detach(source) else {
+            return vec![];
+        };
+
+        self.targets(source)
+            .iter()
+            .filter_map(|target| target.attach_with(world.arena::<B>(), world).ok())
+            .collect()
+    }
+
+    pub fn source_handles<'arena, W>(&self, world: &'arena W, target: Index) -> Vec<A::Handle>
+    where
+        W: World + WorldArena<A> + WorldArena<B>,
+        A: Handleable<'arena> + 'arena,
+        A::Handle: Handle<'arena, Userdata = &'arena W>,
+    {
+        let Ok(target) = world.arena::<B>().detach(target) else {
+            return vec![];
+        };
+
+        self.sources(target)
+            .iter()
+            .filter_map(|source| source.attach_with(world.arena::<A>(), world).ok())
+            .collect()
+    }
+
+    pub fn target_handle<'arena, W>(&self, world: &'arena W, source: Index) -> Option<B::Handle>
+    where
+        W: World + WorldArena<A> + WorldArena<B>,
+        B: Handleable<'arena> + 'arena,
+        B::Handle: Handle<'arena, Userdata = &'arena W>,
+    {
+        self.target_handles(world, source).into_iter().next()
+    }
+
+    pub fn source_handle<'arena, W>(&self, world: &'arena W, target: Index) -> Option<A::Handle>
+    where
+        W: World + WorldArena<A> + WorldArena<B>,
+        A: Handleable<'arena> + 'arena,
+        A::Handle: Handle<'arena, Userdata = &'arena W>,
+    {
+        self.source_handles(world, target).into_iter().next()
+    }
+}
+
+fn detach_from<K: Eq + Hash, V: PartialEq>(
+    links: &mut HashMap<K, Vec<V>>,
+    key: K,
+    value: V,
+) -> bool {
+    let Some(values) = links.get_mut(&key) else {
+        return false;
+    };
+
+    let len = values.len();
+    values.retain(|v| *v != value);
+    let detached = values.len() != len;
+
+    if values.is_empty() {
+        links.remove(&key);
+    }
+
+    detached
+}
+
+impl<A, B> Default for Relation<A, B> {
+    fn default() -> Self {
+        Self::many_to_many()
+    }
+}
+
+impl<A, B> Clone for Relation<A, B> {
+    fn clone(&self) -> Self {
+        Self {
+            kind: self.kind,
+            targets: self.targets.clone(),
+            sources: self.sources.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A, B> fmt::Debug for Relation<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let links = self.targets.iter().flat_map(|(source, targets)| {
+            iter::repeat(source).zip(targets).map(|(source, target)| {
+                (
+                    <Index as Into<i64>>::into(source.index()),
+                    <Index as Into<i64>>::into(target.index()),
+                )
+            })
+        });
+
+        f.debug_struct("Relation")
+            .field("kind", &self.kind)
+            .field("links", &links.collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+// `element` is the `DetachedHandle` of the removed element.
+pub trait RelationStore {
+    fn remove_links(&mut self, element: &dyn Any);
+}
+
+impl<A: 'static, B: 'static> RelationStore for Relation<A, B> {
+    fn remove_links(&mut self, element: &dyn Any) {
+        if let Some(&source) = element.downcast_ref::<DetachedHandle<A>>() {
+            self.remove_source(source);
+        }
+
+        if let Some(&target) = element.downcast_ref::<DetachedHandle<B>>() {
+            self.remove_target(target);
+        }
+    }
+}
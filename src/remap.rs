@@ -0,0 +1,200 @@
+use crate::{Arena, ArenaError, ArenaResult, Index, References};
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+use std::marker::PhantomData;
+
+// Maps elements to their new indices after they were moved or copied. References to them are
+// rewritten to the new indices, other references are kept only if they still point at an element
+// of the arena they are in, and become invalid otherwise.
+pub struct Remap<T> {
+    map: BTreeMap<Index, Index>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Remap<T> {
+    pub fn new() -> Self {
+        Self { map: BTreeMap::new(), _marker: PhantomData }
+    }
+
+    pub fn insert(&mut self, old: Index, new: Index) {
+        self.map.insert(old, new);
+    }
+
+    pub fn get(&self, old: Index) -> Option<Index> {
+        self.map.get(&old).copied()
+    }
+
+    // For references into another arena, e.g. the one elements were merged from, where any element
+    // which wasn't moved is gone.
+    pub fn remap(&self, index: &mut Index) {
+        *index = self.get(*index).unwrap_or_else(Index::invalid);
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Index, Index)> + '_ {
+        self.map.iter().map(|(old, new)| (*old, *new))
+    }
+
+    pub fn new_indices(&self) -> impl Iterator<Item = Index> + '_ {
+        self.map.values().copied()
+    }
+}
+
+impl<T> Default for Remap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Remap<T> {
+    fn clone(&self) -> Self {
+        Self { map: self.map.clone(), _marker: PhantomData }
+    }
+}
+
+impl<T> fmt::Debug for Remap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self
+            .iter()
+            .map(|(old, new)| (<Index as Into<i64>>::into(old), <Index as Into<i64>>::into(new)));
+
+        f.debug_map().entries(entries).finish()
+    }
+}
+
+impl<T> Arena<T> {
+    // Fails without changing the arena if an element of `other` violates a unique index. References
+    // of merged elements are remapped by `merge_remapped`, references to elements which weren't
+    // merged point into `other`, so they become invalid.
+    pub fn merge(&mut self, mut other: Arena<T>) -> ArenaResult<Remap<T>> {
+        {
+            let elements = other.iter().map(|(_, element)| element).collect::<Vec<_>>();
+            self.check_indexes(elements.iter().map(|element| &**element))?;
+        }
+
+        let mut remap = Remap::new();
+        other.indices().collect::<Vec<_>>().into_iter().try_for_each(|old| {
+            let value = other.remove(old)?;
+            remap.insert(old, self.try_add(value)?);
+
+            Ok::<_, ArenaError>(())
+        })?;
+
+        Ok(remap)
+    }
+
+    pub fn merge_remapped(&mut self, other: Arena<T>) -> ArenaResult<Remap<T>>
+    where
+        T: References,
+    {
+        let remap = self.merge(other)?;
+        self.remap_references(remap.new_indices(), &remap);
+
+        Ok(remap)
+    }
+
+    pub fn remap_references<U>(
+        &mut self,
+        indices: impl IntoIterator<Item = Index>,
+        remap: &Remap<U>,
+    ) where
+        T: References<U>,
+    {
+        indices.into_iter().for_each(|index| {
+            if let Ok(mut element) = self.lookup_mut(index) {
+                element.visit_references_mut(&mut |_, reference| remap.remap(reference));
+            }
+        });
+    }
+
+    pub fn clone_subgraph(
+        &mut self,
+        roots: impl IntoIterator<Item = Index>,
+    ) -> ArenaResult<Remap<T>>
+    where
+        T: Clone + References,
+    {
+        self.clone_subgraph_by(roots, |_| true)
+    }
+
+    // Only references in fields accepted by `follow` are traversed. References between copied
+    // elements point at the copies, references to elements outside of the subgraph are kept since
+    // they are in the same arena, and dangling ones become invalid.
+    pub fn clone_subgraph_by(
+        &mut self,
+        roots: impl IntoIterator<Item = Index>,
+        mut follow: impl FnMut(&'static str) -> bool,
+    ) -> ArenaResult<Remap<T>>
+    where
+        T: Clone + References,
+    {
+        let mut visited = BTreeSet::new();
+        let mut queue = VecDeque::new();
+        roots.into_iter().try_for_each(|root| {
+            if !root.is_invalid() && !self.contains(root) {
+                return Err(ArenaError::RemovedElementAccess);
+            }
+
+            if visited.insert(root) {
+                queue.push_back(root);
+            }
+
+            Ok(())
+        })?;
+
+        let mut order = vec![];
+        while let Some(index) = queue.pop_front() {
+            self.lookup(index)?.visit_references(&mut |field, reference| {
+                if follow(field) && self.contains(reference) && visited.insert(reference) {
+                    queue.push_back(reference);
+                }
+            });
+
+            order.push(index);
+        }
+
+        // Dangling references are invalidated before the copies are added, since they could
+        // take the slots those references point at.
+        let copies = order
+            .iter()
+            .map(|old| {
+                let mut copy = self.lookup(*old)?.clone();
+                copy.visit_references_mut(&mut |_, reference| {
+                    if !self.contains(*reference) {
+                        *reference = Index::invalid();
+                    }
+                });
+
+                Ok(copy)
+            })
+            .collect::<ArenaResult<Vec<_>>>()?;
+        self.check_indexes(&copies)?;
+
+        let mut remap = Remap::new();
+        order.into_iter().zip(copies).try_for_each(|(old, value)| {
+            remap.insert(old, self.try_add(value)?);
+
+            Ok::<_, ArenaError>(())
+        })?;
+
+        remap.new_indices().for_each(|index| {
+            if let Ok(mut element) = self.lookup_mut(index) {
+                element.visit_references_mut(&mut |_, reference| {
+                    if let Some(new) = remap.get(*reference) {
+                        *reference = new;
+                    }
+                });
+            }
+        });
+
+        Ok(remap)
+    }
+}
@@ -0,0 +1,380 @@
+use crate::{Arena, ArenaError, ArenaResult, DetachedHandle, ElementRef, ElementRefMut, Index};
+use crate::{RawSharedHandle, SharedHandle, SharedHandleable};
+
+use std::cell::{self, RefCell};
+use std::ops;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+
+pub trait SharedArena<T>: Clone {
+    type Ref<'a, U: 'a>: ops::Deref<Target = U>
+    where
+        Self: 'a;
+    type RefMut<'a, U: 'a>: ops::DerefMut<Target = U>
+    where
+        Self: 'a;
+
+    fn lookup<'a>(&'a self, index: Index) -> ArenaResult<Self::Ref<'a, T>>
+    where
+        T: 'a;
+    fn lookup_mut<'a>(&'a self, index: Index) -> ArenaResult<Self::RefMut<'a, T>>
+    where
+        T: 'a;
+
+    // Same as `lookup`, but fails with `ArenaBorrowed` instead of waiting for the arena.
+    fn try_lookup<'a>(&'a self, index: Index) -> ArenaResult<Self::Ref<'a, T>>
+    where
+        T: 'a,
+    {
+        self.lookup(index)
+    }
+
+    fn try_lookup_mut<'a>(&'a self, index: Index) -> ArenaResult<Self::RefMut<'a, T>>
+    where
+        T: 'a,
+    {
+        self.lookup_mut(index)
+    }
+
+    fn detach(&self, index: Index) -> ArenaResult<DetachedHandle<T>>;
+    fn with_arena<R>(&self, f: impl FnOnce(&Arena<T>) -> ArenaResult<R>) -> ArenaResult<R>;
+
+    fn map_ref<'a, U, V>(element: Self::Ref<'a, U>, f: impl FnOnce(&U) -> &V) -> Self::Ref<'a, V>;
+    fn map_ref_mut<'a, U, V>(
+        element: Self::RefMut<'a, U>,
+        f: impl FnOnce(&mut U) -> &mut V,
+    ) -> Self::RefMut<'a, V>;
+}
+
+// `element` borrows from the arena behind `_guard`, so it must be declared (and dropped) first.
+pub struct SharedRef<'a, G, U> {
+    element: ElementRef<'a, U>,
+    _guard: G,
+}
+
+impl<'a, G, U> SharedRef<'a, G, U> {
+    pub fn map<V>(this: Self, f: impl FnOnce(&U) -> &V) -> SharedRef<'a, G, V> {
+        SharedRef { element: ElementRef::map(this.element, f), _guard: this._guard }
+    }
+}
+
+impl<G, U> ops::Deref for SharedRef<'_, G, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        &self.element
+    }
+}
+
+pub struct SharedRefMut<'a, G, U> {
+    element: ElementRefMut<'a, U>,
+    _guard: G,
+}
+
+impl<'a, G, U> SharedRefMut<'a, G, U> {
+    pub fn map<V>(this: Self, f: impl FnOnce(&mut U) -> &mut V) -> SharedRefMut<'a, G, V> {
+        SharedRefMut { element: ElementRefMut::map(this.element, f), _guard: this._guard }
+    }
+}
+
+impl<G, U> ops::Deref for SharedRefMut<'_, G, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        &self.element
+    }
+}
+
+impl<G, U> ops::DerefMut for SharedRefMut<'_, G, U> {
+    fn deref_mut(&mut self) -> &mut U {
+        &mut self.element
+    }
+}
+
+pub type RcRef<'a, T, U> = SharedRef<'a, cell::Ref<'a, Arena<T>>, U>;
+pub type RcRefMut<'a, T, U> = SharedRefMut<'a, cell::Ref<'a, Arena<T>>, U>;
+
+#[derive(Debug)]
+pub struct RcArena<T> {
+    arena: Rc<RefCell<Arena<T>>>,
+}
+
+impl<T> RcArena<T> {
+    pub fn new() -> Self {
+        Self::from(Arena::new())
+    }
+
+    pub fn len(&self) -> usize {
+        self.arena.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Panics if the arena is borrowed or `value` violates a unique index, see `try_add`.
+    pub fn add(&self, value: T) -> Index {
+        self.try_add(value).expect("failed to add element")
+    }
+
+    pub fn try_add(&self, value: T) -> ArenaResult<Index> {
+        let mut arena = self.arena.try_borrow_mut().map_err(|_| ArenaError::ArenaBorrowed)?;
+
+        arena.try_add(value)
+    }
+
+    pub fn remove(&self, index: Index) -> ArenaResult<T> {
+        let mut arena = self.arena.try_borrow_mut().map_err(|_| ArenaError::ArenaBorrowed)?;
+
+        arena.remove(index)
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.arena.borrow().contains(index)
+    }
+
+    pub fn handle(&self, index: Index) -> T::SharedHandle<Self>
+    where
+        T: SharedHandleable,
+    {
+        T::SharedHandle::from_raw(RawSharedHandle::new(self.clone(), index))
+    }
+
+    pub fn borrow(&self) -> cell::Ref<'_, Arena<T>> {
+        self.arena.borrow()
+    }
+}
+
+impl<T> SharedArena<T> for RcArena<T> {
+    type Ref<'a, U: 'a>
+        = RcRef<'a, T, U>
+    where
+        Self: 'a;
+    type RefMut<'a, U: 'a>
+        = RcRefMut<'a, T, U>
+    where
+        Self: 'a;
+
+    fn lookup<'a>(&'a self, index: Index) -> ArenaResult<Self::Ref<'a, T>>
+    where
+        T: 'a,
+    {
+        let guard = self.arena.try_borrow().map_err(|_| ArenaError::ArenaBorrowed)?;
+        // SAFETY: the arena can't be mutated or dropped while `guard` is alive, and the element
+        // reference is dropped before `guard`.
+        let arena = unsafe { &*(&*guard as *const Arena<T>) };
+
+        Ok(SharedRef { element: arena.lookup(index)?, _guard: guard })
+    }
+
+    fn lookup_mut<'a>(&'a self, index: Index) -> ArenaResult<Self::RefMut<'a, T>>
+    where
+        T: 'a,
+    {
+        let guard = self.arena.try_borrow().map_err(|_| ArenaError::ArenaBorrowed)?;
+        // SAFETY: see `lookup`.
+        let arena = unsafe { &*(&*guard as *const Arena<T>) };
+
+        Ok(SharedRefMut { element: arena.lookup_mut(index)?, _guard: guard })
+    }
+
+    fn detach(&self, index: Index) -> ArenaResult<DetachedHandle<T>> {
+        self.arena
+            .try_borrow()
+            .map_err(|_| ArenaError::ArenaBorrowed)?
+            .detach(index)
+    }
+
+    fn with_arena<R>(&self, f: impl FnOnce(&Arena<T>) -> ArenaResult<R>) -> ArenaResult<R> {
+        f(&*self.arena.try_borrow().map_err(|_| ArenaError::ArenaBorrowed)?)
+    }
+
+    fn map_ref<'a, U, V>(element: Self::Ref<'a, U>, f: impl FnOnce(&U) -> &V) -> Self::Ref<'a, V> {
+        SharedRef::map(element, f)
+    }
+
+    fn map_ref_mut<'a, U, V>(
+        element: Self::RefMut<'a, U>,
+        f: impl FnOnce(&mut U) -> &mut V,
+    ) -> Self::RefMut<'a, V> {
+        SharedRefMut::map(element, f)
+    }
+}
+
+impl<T> Default for RcArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for RcArena<T> {
+    fn clone(&self) -> Self {
+        Self { arena: Rc::clone(&self.arena) }
+    }
+}
+
+impl<T> From<Arena<T>> for RcArena<T> {
+    fn from(arena: Arena<T>) -> Self {
+        Self { arena: Rc::new(RefCell::new(arena)) }
+    }
+}
+
+pub type ArcRef<'a, T, U> = SharedRef<'a, MutexGuard<'a, Arena<T>>, U>;
+pub type ArcRefMut<'a, T, U> = SharedRefMut<'a, MutexGuard<'a, Arena<T>>, U>;
+
+// Elements are accessed under a lock of the whole arena, so holding a reference returned by a
+// handle blocks other threads. Accessing the arena again on the same thread while such a reference
+// is alive, e.g. calling a setter, deadlocks. `try_lock`, `try_lookup` and `try_get` of handles
+// fail with `ArenaBorrowed` instead of waiting.
+#[derive(Debug)]
+pub struct ArcArena<T> {
+    arena: Arc<Mutex<Arena<T>>>,
+}
+
+impl<T> ArcArena<T> {
+    pub fn new() -> Self {
+        Self::from(Arena::new())
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn add(&self, value: T) -> Index {
+        self.lock().add(value)
+    }
+
+    pub fn try_add(&self, value: T) -> ArenaResult<Index> {
+        self.lock().try_add(value)
+    }
+
+    pub fn remove(&self, index: Index) -> ArenaResult<T> {
+        self.lock().remove(index)
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.lock().contains(index)
+    }
+
+    pub fn handle(&self, index: Index) -> T::SharedHandle<Self>
+    where
+        T: SharedHandleable,
+    {
+        T::SharedHandle::from_raw(RawSharedHandle::new(self.clone(), index))
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, Arena<T>> {
+        self.arena.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn try_lock(&self) -> ArenaResult<MutexGuard<'_, Arena<T>>> {
+        match self.arena.try_lock() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(err)) => Ok(err.into_inner()),
+            Err(TryLockError::WouldBlock) => Err(ArenaError::ArenaBorrowed),
+        }
+    }
+}
+
+impl<T> ArcArena<T> {
+    fn lookup_locked(
+        guard: MutexGuard<'_, Arena<T>>,
+        index: Index,
+    ) -> ArenaResult<ArcRef<'_, T, T>> {
+        // SAFETY: the arena can't be accessed by anyone else while `guard` is alive, and the
+        // element reference is dropped before `guard`.
+        let arena = unsafe { &*(&*guard as *const Arena<T>) };
+
+        Ok(SharedRef { element: arena.lookup(index)?, _guard: guard })
+    }
+
+    fn lookup_mut_locked(
+        guard: MutexGuard<'_, Arena<T>>,
+        index: Index,
+    ) -> ArenaResult<ArcRefMut<'_, T, T>> {
+        // SAFETY: see `lookup_locked`.
+        let arena = unsafe { &*(&*guard as *const Arena<T>) };
+
+        Ok(SharedRefMut { element: arena.lookup_mut(index)?, _guard: guard })
+    }
+}
+
+impl<T> SharedArena<T> for ArcArena<T> {
+    type Ref<'a, U: 'a>
+        = ArcRef<'a, T, U>
+    where
+        Self: 'a;
+    type RefMut<'a, U: 'a>
+        = ArcRefMut<'a, T, U>
+    where
+        Self: 'a;
+
+    fn lookup<'a>(&'a self, index: Index) -> ArenaResult<Self::Ref<'a, T>>
+    where
+        T: 'a,
+    {
+        Self::lookup_locked(self.lock(), index)
+    }
+
+    fn lookup_mut<'a>(&'a self, index: Index) -> ArenaResult<Self::RefMut<'a, T>>
+    where
+        T: 'a,
+    {
+        Self::lookup_mut_locked(self.lock(), index)
+    }
+
+    fn try_lookup<'a>(&'a self, index: Index) -> ArenaResult<Self::Ref<'a, T>>
+    where
+        T: 'a,
+    {
+        Self::lookup_locked(self.try_lock()?, index)
+    }
+
+    fn try_lookup_mut<'a>(&'a self, index: Index) -> ArenaResult<Self::RefMut<'a, T>>
+    where
+        T: 'a,
+    {
+        Self::lookup_mut_locked(self.try_lock()?, index)
+    }
+
+    fn detach(&self, index: Index) -> ArenaResult<DetachedHandle<T>> {
+        self.lock().detach(index)
+    }
+
+    fn with_arena<R>(&self, f: impl FnOnce(&Arena<T>) -> ArenaResult<R>) -> ArenaResult<R> {
+        f(&self.lock())
+    }
+
+    fn map_ref<'a, U, V>(element: Self::Ref<'a, U>, f: impl FnOnce(&U) -> &V) -> Self::Ref<'a, V> {
+        SharedRef::map(element, f)
+    }
+
+    fn map_ref_mut<'a, U, V>(
+        element: Self::RefMut<'a, U>,
+        f: impl FnOnce(&mut U) -> &mut V,
+    ) -> Self::RefMut<'a, V> {
+        SharedRefMut::map(element, f)
+    }
+}
+
+impl<T> Default for ArcArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for ArcArena<T> {
+    fn clone(&self) -> Self {
+        Self { arena: Arc::clone(&self.arena) }
+    }
+}
+
+impl<T> From<Arena<T>> for ArcArena<T> {
+    fn from(arena: Arena<T>) -> Self {
+        Self { arena: Arc::new(Mutex::new(arena)) }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{ArenaResult, DetachedHandle, Index, SharedArena};
+
+use std::marker::PhantomData;
+use std::{cmp, fmt};
+
+pub trait SharedHandleable: Sized {
+    type SharedHandle<A: SharedArena<Self>>: SharedHandle<Type = Self, Arena = A>;
+}
+
+pub trait SharedHandle: Sized {
+    type Type;
+    type Arena: SharedArena<Self::Type>;
+
+    fn from_raw(raw: RawSharedHandle<Self::Arena, Self::Type>) -> Self;
+    fn to_raw(&self) -> &RawSharedHandle<Self::Arena, Self::Type>;
+
+    fn get<'a>(
+        &'a self,
+    ) -> ArenaResult<<Self::Arena as SharedArena<Self::Type>>::Ref<'a, Self::Type>>
+    where
+        Self::Type: 'a,
+    {
+        self.to_raw().get()
+    }
+
+    fn get_mut<'a>(
+        &'a self,
+    ) -> ArenaResult<<Self::Arena as SharedArena<Self::Type>>::RefMut<'a, Self::Type>>
+    where
+        Self::Type: 'a,
+    {
+        self.to_raw().get_mut()
+    }
+
+    fn try_get<'a>(
+        &'a self,
+    ) -> ArenaResult<<Self::Arena as SharedArena<Self::Type>>::Ref<'a, Self::Type>>
+    where
+        Self::Type: 'a,
+    {
+        self.to_raw().try_get()
+    }
+
+    fn try_get_mut<'a>(
+        &'a self,
+    ) -> ArenaResult<<Self::Arena as SharedArena<Self::Type>>::RefMut<'a, Self::Type>>
+    where
+        Self::Type: 'a,
+    {
+        self.to_raw().try_get_mut()
+    }
+
+    fn exists(&self) -> bool {
+        self.to_raw().get().is_ok()
+    }
+
+    fn arena(&self) -> &Self::Arena {
+        self.to_raw().arena()
+    }
+
+    fn index(&self) -> Index {
+        self.to_raw().index()
+    }
+
+    fn detach(&self) -> ArenaResult<DetachedHandle<Self::Type>> {
+        self.arena().detach(self.index())
+    }
+}
+
+pub struct RawSharedHandle<A, T> {
+    arena: A,
+    index: Index,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<A, T> RawSharedHandle<A, T> {
+    pub(crate) fn new(arena: A, index: Index) -> Self {
+        Self { arena, index, _marker: PhantomData }
+    }
+}
+
+impl<A: SharedArena<T>, T> RawSharedHandle<A, T> {
+    fn get<'a>(&'a self) -> ArenaResult<A::Ref<'a, T>>
+    where
+        T: 'a,
+    {
+        self.arena.lookup(self.index)
+    }
+
+    fn get_mut<'a>(&'a self) -> ArenaResult<A::RefMut<'a, T>>
+    where
+        T: 'a,
+    {
+        self.arena.lookup_mut(self.index)
+    }
+
+    fn try_get<'a>(&'a self) -> ArenaResult<A::Ref<'a, T>>
+    where
+        T: 'a,
+    {
+        self.arena.try_lookup(self.index)
+    }
+
+    fn try_get_mut<'a>(&'a self) -> ArenaResult<A::RefMut<'a, T>>
+    where
+        T: 'a,
+    {
+        self.arena.try_lookup_mut(self.index)
+    }
+
+    fn arena(&self) -> &A {
+        &self.arena
+    }
+
+    fn index(&self) -> Index {
+        self.index
+    }
+}
+
+impl<A, T> fmt::Debug for RawSharedHandle<A, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("SharedHandle({})", <Index as Into<i64>>::into(self.index)))
+    }
+}
+
+impl<A: SharedArena<T>, T> Clone for RawSharedHandle<A, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.arena.clone(), self.index)
+    }
+}
+
+impl<A: SharedArena<T>, T> cmp::PartialEq for RawSharedHandle<A, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<A: SharedArena<T>, T> cmp::Eq for RawSharedHandle<A, T> {}
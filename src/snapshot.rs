@@ -0,0 +1,455 @@
+use crate::{Arena, ArenaError, ArenaResult, DenseStorage, Index, Storage};
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+const MAGIC: [u8; 6] = *b"ARSNAP";
+
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+// Values are encoded compactly: unsigned integers and lengths as LEB128 varints, signed integers
+// zigzag encoded on top of that and floats as their little-endian bits.
+pub trait SnapshotValue: Sized {
+    fn encode(&self, writer: &mut SnapshotWriter);
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self>;
+}
+
+// Elements are stored with the schema version they were written with. `SNAPSHOT_NAME` identifies
+// their arena in a snapshot, so it should stay the same when the type is renamed or moved.
+pub trait SnapshotElement: SnapshotValue {
+    const SNAPSHOT_NAME: &'static str;
+    const SCHEMA_VERSION: u32;
+}
+
+#[derive(Debug, Default)]
+pub struct SnapshotWriter {
+    bytes: Vec<u8>,
+}
+
+impl SnapshotWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write<V: SnapshotValue>(&mut self, value: &V) {
+        value.encode(self);
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_varint(bytes.len() as u64);
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn write_varint(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+
+            if value == 0 {
+                self.bytes.push(byte);
+                return;
+            }
+            self.bytes.push(byte | 0x80);
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[derive(Debug)]
+pub struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SnapshotReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn read<V: SnapshotValue>(&mut self) -> ArenaResult<V> {
+        V::decode(self)
+    }
+
+    pub fn read_bytes(&mut self) -> ArenaResult<&'a [u8]> {
+        let len = self.read_varint()? as usize;
+
+        self.take(len)
+    }
+
+    pub fn read_varint(&mut self) -> ArenaResult<u64> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            // Only the lowest bit of the tenth byte still fits.
+            if shift == 63 && byte & 0x7f > 1 {
+                return Err(ArenaError::SnapshotCorrupted("varint overflows 64 bits"));
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(ArenaError::SnapshotCorrupted("varint is too long"))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> ArenaResult<&'a [u8]> {
+        if len > self.bytes.len() {
+            return Err(ArenaError::SnapshotCorrupted("unexpected end of data"));
+        }
+
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+}
+
+macro_rules! impl_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl SnapshotValue for $ty {
+                fn encode(&self, writer: &mut SnapshotWriter) {
+                    writer.write_varint(*self as u64);
+                }
+
+                fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+                    <$ty>::try_from(reader.read_varint()?)
+                        .map_err(|_| ArenaError::SnapshotCorrupted("integer is out of range"))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($ty:ty),*) => {
+        $(
+            impl SnapshotValue for $ty {
+                fn encode(&self, writer: &mut SnapshotWriter) {
+                    let value = *self as i64;
+                    writer.write_varint(((value << 1) ^ (value >> 63)) as u64);
+                }
+
+                fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+                    let value = reader.read_varint()?;
+                    let value = (value >> 1) as i64 ^ -((value & 1) as i64);
+
+                    <$ty>::try_from(value)
+                        .map_err(|_| ArenaError::SnapshotCorrupted("integer is out of range"))
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, usize);
+impl_signed!(i8, i16, i32, i64, isize);
+
+impl SnapshotValue for f32 {
+    fn encode(&self, writer: &mut SnapshotWriter) {
+        writer.bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+        Ok(f32::from_le_bytes(reader.take(4)?.try_into().unwrap()))
+    }
+}
+
+impl SnapshotValue for f64 {
+    fn encode(&self, writer: &mut SnapshotWriter) {
+        writer.bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+        Ok(f64::from_le_bytes(reader.take(8)?.try_into().unwrap()))
+    }
+}
+
+impl SnapshotValue for bool {
+    fn encode(&self, writer: &mut SnapshotWriter) {
+        writer.bytes.push(*self as u8);
+    }
+
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+        match reader.take(1)?[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(ArenaError::SnapshotCorrupted("invalid bool")),
+        }
+    }
+}
+
+impl SnapshotValue for String {
+    fn encode(&self, writer: &mut SnapshotWriter) {
+        writer.write_bytes(self.as_bytes());
+    }
+
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+        let bytes = reader.read_bytes()?;
+
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| ArenaError::SnapshotCorrupted("invalid utf-8 string"))
+    }
+}
+
+impl SnapshotValue for Index {
+    fn encode(&self, writer: &mut SnapshotWriter) {
+        writer.write(&<Index as Into<i64>>::into(*self));
+    }
+
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+        Ok(Index::new(reader.read()?))
+    }
+}
+
+impl<V: SnapshotValue> SnapshotValue for Option<V> {
+    fn encode(&self, writer: &mut SnapshotWriter) {
+        writer.write(&self.is_some());
+        if let Some(value) = self {
+            writer.write(value);
+        }
+    }
+
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+        match reader.read::<bool>()? {
+            true => Ok(Some(reader.read()?)),
+            false => Ok(None),
+        }
+    }
+}
+
+impl<V: SnapshotValue> SnapshotValue for Vec<V> {
+    fn encode(&self, writer: &mut SnapshotWriter) {
+        writer.write_varint(self.len() as u64);
+        self.iter().for_each(|value| writer.write(value));
+    }
+
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+        let len = reader.read_varint()? as usize;
+
+        // Every value takes at least one byte, so corrupted lengths can't reserve too much.
+        let mut values = Vec::with_capacity(len.min(reader.bytes.len()));
+        (0..len).try_for_each(|_| {
+            values.push(reader.read()?);
+
+            Ok::<_, ArenaError>(())
+        })?;
+
+        Ok(values)
+    }
+}
+
+type Migration<T> = Box<dyn Fn(&mut SnapshotReader<'_>) -> ArenaResult<T>>;
+
+// Decoders for elements written with older schema versions, which convert them straight into the
+// current type.
+pub struct SnapshotMigrations<T> {
+    migrations: HashMap<u32, Migration<T>>,
+}
+
+impl<T: SnapshotElement> SnapshotMigrations<T> {
+    pub fn new() -> Self {
+        Self { migrations: HashMap::new() }
+    }
+
+    pub fn register(
+        &mut self,
+        version: u32,
+        migration: impl Fn(&mut SnapshotReader<'_>) -> ArenaResult<T> + 'static,
+    ) -> &mut Self {
+        self.migrations.insert(version, Box::new(migration));
+
+        self
+    }
+
+    fn decode(&self, version: u32, reader: &mut SnapshotReader<'_>) -> ArenaResult<T> {
+        if version == T::SCHEMA_VERSION {
+            return T::decode(reader);
+        }
+
+        let migration = self
+            .migrations
+            .get(&version)
+            .ok_or(ArenaError::UnsupportedSnapshotVersion(T::SNAPSHOT_NAME, version))?;
+
+        migration(reader)
+    }
+}
+
+impl<T: SnapshotElement> Default for SnapshotMigrations<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for SnapshotMigrations<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.migrations.keys()).finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SnapshotSection {
+    version: u32,
+    bytes: Vec<u8>,
+}
+
+// Arenas of a world are stored as sections keyed by element name. Slots keep their indices and
+// generations, so references between arenas stay valid after loading, and vacant slots are reused
+// in the same order as before saving.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    sections: BTreeMap<String, SnapshotSection>,
+}
+
+impl Snapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Replaces the arena stored for the same element type. Fails if an element is borrowed
+    // mutably.
+    pub fn add_arena<T: SnapshotElement, S: ?Sized + Storage<T>>(
+        &mut self,
+        arena: &Arena<T, S>,
+    ) -> ArenaResult<()> {
+        let mut writer = SnapshotWriter::new();
+        writer.write_varint(arena.capacity() as u64);
+        (0..arena.capacity()).try_for_each(|slot| {
+            writer.write(&arena.storage().generation(slot));
+            writer.write(&arena.storage().is_occupied(slot));
+
+            if arena.storage().is_occupied(slot) {
+                let mut element = SnapshotWriter::new();
+                element.write(&*arena.lookup(Index::from(slot))?);
+                writer.write_bytes(&element.bytes);
+            }
+
+            Ok::<_, ArenaError>(())
+        })?;
+        writer.write(&arena.storage().vacant().collect::<Vec<_>>());
+
+        self.sections.insert(
+            T::SNAPSHOT_NAME.to_string(),
+            SnapshotSection { version: T::SCHEMA_VERSION, bytes: writer.into_bytes() },
+        );
+
+        Ok(())
+    }
+
+    pub fn has_arena<T: SnapshotElement>(&self) -> bool {
+        self.sections.contains_key(T::SNAPSHOT_NAME)
+    }
+
+    pub fn schema_version<T: SnapshotElement>(&self) -> Option<u32> {
+        self.sections.get(T::SNAPSHOT_NAME).map(|section| section.version)
+    }
+
+    pub fn load_arena<T: SnapshotElement>(&self) -> ArenaResult<Arena<T>> {
+        self.load_arena_with(&SnapshotMigrations::new())
+    }
+
+    // Elements are decoded one by one, running the migration registered for the version they were
+    // written with.
+    pub fn load_arena_with<T: SnapshotElement>(
+        &self,
+        migrations: &SnapshotMigrations<T>,
+    ) -> ArenaResult<Arena<T>> {
+        let section = self
+            .sections
+            .get(T::SNAPSHOT_NAME)
+            .ok_or(ArenaError::MissingSnapshotArena(T::SNAPSHOT_NAME))?;
+
+        let mut reader = SnapshotReader::new(&section.bytes);
+        let capacity = reader.read_varint()? as usize;
+
+        let mut slots = Vec::with_capacity(capacity.min(section.bytes.len()));
+        let mut generations = Vec::with_capacity(capacity.min(section.bytes.len()));
+        (0..capacity).try_for_each(|_| {
+            generations.push(reader.read()?);
+
+            let element = match reader.read::<bool>()? {
+                true => {
+                    let mut element = SnapshotReader::new(reader.read_bytes()?);
+                    let decoded = migrations.decode(section.version, &mut element)?;
+                    if !element.is_empty() {
+                        return Err(ArenaError::SnapshotCorrupted("trailing element data"));
+                    }
+
+                    Some(decoded)
+                }
+                false => None,
+            };
+            slots.push(element);
+
+            Ok::<_, ArenaError>(())
+        })?;
+
+        // Vacant slots are stored in the order they're reused, which has to list each of them.
+        let vacant = reader.read::<Vec<usize>>()?;
+        let mut sorted = vacant.clone();
+        sorted.sort_unstable();
+        if !sorted
+            .iter()
+            .copied()
+            .eq((0..capacity).filter(|slot| slots[*slot].is_none()))
+        {
+            return Err(ArenaError::SnapshotCorrupted("vacant slots don't match"));
+        }
+        if !reader.is_empty() {
+            return Err(ArenaError::SnapshotCorrupted("trailing arena data"));
+        }
+
+        Ok(Arena::with_storage(DenseStorage::from_slots(slots, generations, vacant)))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = SnapshotWriter::new();
+        writer.bytes.extend_from_slice(&MAGIC);
+        writer.write(&SNAPSHOT_FORMAT_VERSION);
+        writer.write_varint(self.sections.len() as u64);
+        self.sections.iter().for_each(|(name, section)| {
+            writer.write(name);
+            writer.write(&section.version);
+            writer.write_bytes(&section.bytes);
+        });
+
+        writer.into_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> ArenaResult<Self> {
+        let mut reader = SnapshotReader::new(bytes);
+        if reader.take(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+            return Err(ArenaError::SnapshotCorrupted("missing snapshot header"));
+        }
+
+        let format_version = reader.read()?;
+        if format_version != SNAPSHOT_FORMAT_VERSION {
+            return Err(ArenaError::UnsupportedSnapshotVersion("snapshot format", format_version));
+        }
+
+        let mut sections = BTreeMap::new();
+        (0..reader.read_varint()?).try_for_each(|_| {
+            let name = reader.read::<String>()?;
+            let version = reader.read()?;
+            let bytes = reader.read_bytes()?.to_vec();
+            sections.insert(name, SnapshotSection { version, bytes });
+
+            Ok::<_, ArenaError>(())
+        })?;
+
+        if !reader.is_empty() {
+            return Err(ArenaError::SnapshotCorrupted("trailing data"));
+        }
+
+        Ok(Self { sections })
+    }
+}
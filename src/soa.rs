@@ -0,0 +1,155 @@
+use crate::{ArenaError, ArenaResult, ElementRef, ElementRefMut, Index, TryClone};
+
+use vec_cell::{Flatten, VecCell};
+
+// Slots shared by all columns of an arena generated by `#[handleable(soa)]`.
+#[derive(Debug, Clone, Default)]
+pub struct SoaSlots {
+    occupied: Vec<bool>,
+    free: Vec<usize>,
+}
+
+impl SoaSlots {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.occupied.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.occupied.len()
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        if index.is_invalid() {
+            return false;
+        }
+
+        self.occupied.get(<Index as Into<usize>>::into(index)) == Some(&true)
+    }
+
+    pub fn indices(&self) -> impl Iterator<Item = Index> + '_ {
+        (0..self.occupied.len())
+            .map(Index::from)
+            .filter(|&index| self.contains(index))
+    }
+
+    // Columns have to insert a value at the returned index.
+    pub fn allocate(&mut self) -> Index {
+        let slot = self.free.pop().unwrap_or_else(|| {
+            self.occupied.push(false);
+
+            self.occupied.len() - 1
+        });
+        self.occupied[slot] = true;
+
+        Index::from(slot)
+    }
+
+    // Columns have to take their values out of the released index.
+    pub fn release(&mut self, index: Index) -> ArenaResult<()> {
+        if index.is_invalid() {
+            return Err(ArenaError::InvalidIndexUsage);
+        }
+
+        if !self.contains(index) {
+            return Err(ArenaError::RemovedElementAccess);
+        }
+
+        self.occupied[<Index as Into<usize>>::into(index)] = false;
+        self.free.push(index.into());
+
+        Ok(())
+    }
+}
+
+// Values of a single field, which can be borrowed independently of other columns.
+#[derive(Debug)]
+pub struct SoaColumn<T> {
+    cells: VecCell<Option<T>>,
+}
+
+impl<T> SoaColumn<T> {
+    pub fn new() -> Self {
+        Self { cells: VecCell::new() }
+    }
+
+    pub fn get(&self, index: Index) -> ArenaResult<ElementRef<'_, T>> {
+        if index.is_invalid() {
+            return Err(ArenaError::InvalidIndexUsage);
+        }
+
+        let element = self.cells.try_borrow(index.into()).flatten()?;
+
+        Ok(ElementRef::from(element))
+    }
+
+    pub fn get_mut(&self, index: Index) -> ArenaResult<ElementRefMut<'_, T>> {
+        if index.is_invalid() {
+            return Err(ArenaError::InvalidIndexUsage);
+        }
+
+        let element = self.cells.try_borrow_mut(index.into()).flatten()?;
+
+        Ok(ElementRefMut::from(element))
+    }
+
+    // Values are expected at indices allocated by `SoaSlots`, so they're either new or vacant.
+    pub fn insert(&mut self, index: Index, value: T) {
+        let slot: usize = index.into();
+        match slot < self.cells.len() {
+            true => *self.cells.try_borrow_mut(slot).unwrap() = Some(value),
+            false => self.cells.push(Some(value)),
+        }
+    }
+
+    pub fn take(&mut self, index: Index) -> Option<T> {
+        self.cells.try_take(index.into()).ok().flatten()
+    }
+
+    // Vacant slots and values which are mutably borrowed are skipped.
+    pub fn iter(&self) -> impl Iterator<Item = (Index, ElementRef<'_, T>)> + '_ {
+        (0..self.cells.len())
+            .map(Index::from)
+            .filter_map(|index| Some((index, self.get(index).ok()?)))
+    }
+
+    // Vacant slots and values which are borrowed are skipped.
+    pub fn iter_mut(&self) -> impl Iterator<Item = (Index, ElementRefMut<'_, T>)> + '_ {
+        (0..self.cells.len())
+            .map(Index::from)
+            .filter_map(|index| Some((index, self.get_mut(index).ok()?)))
+    }
+}
+
+impl<T> Default for SoaColumn<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> TryClone for SoaColumn<T> {
+    fn try_clone(&self) -> ArenaResult<Self> {
+        let mut cells = VecCell::new();
+        (0..self.cells.len()).try_for_each(|slot| {
+            cells.push(self.cells.try_borrow(slot)?.clone());
+
+            Ok::<_, ArenaError>(())
+        })?;
+
+        Ok(Self { cells })
+    }
+}
+
+// Panics if a value is borrowed mutably, see `try_clone`.
+impl<T: Clone> Clone for SoaColumn<T> {
+    fn clone(&self) -> Self {
+        self.try_clone().expect("failed to borrow value for cloning")
+    }
+}
@@ -0,0 +1,184 @@
+use crate::element::BorrowFlag;
+use crate::{
+    Arena, ArenaError, ArenaResult, BorrowError, ElementRef, ElementRefMut, Storage, TryClone,
+};
+
+use std::cell::UnsafeCell;
+use std::fmt;
+
+struct Packed<T> {
+    slot: usize,
+    flag: BorrowFlag,
+    value: UnsafeCell<T>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SparseSlot {
+    position: Option<usize>,
+    generation: u32,
+}
+
+// Live elements are packed contiguously and slots map to their positions, so iterating over
+// `occupied` slots is a linear scan. Removal swaps the last element into the hole, which changes
+// iteration order but not slots.
+pub struct SparseSetStorage<T> {
+    dense: Vec<Packed<T>>,
+    sparse: Vec<SparseSlot>,
+    free: Vec<usize>,
+}
+
+impl<T> SparseSetStorage<T> {
+    pub fn new() -> Self {
+        Self { dense: vec![], sparse: vec![], free: vec![] }
+    }
+
+    fn packed(&self, slot: usize) -> Option<&Packed<T>> {
+        let position = self.sparse.get(slot)?.position?;
+
+        Some(&self.dense[position])
+    }
+
+    fn pack(&mut self, slot: usize, value: T) {
+        self.sparse[slot].position = Some(self.dense.len());
+        self.dense.push(Packed {
+            slot,
+            flag: BorrowFlag::default(),
+            value: UnsafeCell::new(value),
+        });
+    }
+}
+
+impl<T> Storage<T> for SparseSetStorage<T> {
+    fn len(&self) -> usize {
+        self.dense.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.sparse.len()
+    }
+
+    fn next_slot(&self) -> usize {
+        self.free.last().copied().unwrap_or(self.sparse.len())
+    }
+
+    fn insert(&mut self, value: T) -> usize {
+        let slot = self.free.pop().unwrap_or_else(|| {
+            self.sparse.push(SparseSlot { position: None, generation: 0 });
+
+            self.sparse.len() - 1
+        });
+        self.pack(slot, value);
+
+        slot
+    }
+
+    fn insert_at(&mut self, slot: usize, value: T) {
+        if slot == self.sparse.len() {
+            self.sparse.push(SparseSlot { position: None, generation: 0 });
+        } else {
+            let position =
+                self.free.iter().rposition(|&free| free == slot).expect("slot is occupied");
+            self.free.remove(position);
+        }
+        self.pack(slot, value);
+    }
+
+    fn take(&mut self, slot: usize) -> Option<T> {
+        let sparse_slot = self.sparse.get_mut(slot)?;
+        let position = sparse_slot.position.take()?;
+        sparse_slot.generation = sparse_slot.generation.wrapping_add(1);
+        self.free.push(slot);
+
+        let packed = self.dense.swap_remove(position);
+        if let Some(moved) = self.dense.get(position) {
+            self.sparse[moved.slot].position = Some(position);
+        }
+
+        Some(packed.value.into_inner())
+    }
+
+    fn lookup(&self, slot: usize) -> ArenaResult<ElementRef<'_, T>> {
+        let packed = self.packed(slot).ok_or(ArenaError::RemovedElementAccess)?;
+        let flag = packed
+            .flag
+            .try_borrow()
+            .ok_or(BorrowError::ElementAlreadyBorrowedMutably)?;
+
+        // SAFETY: the shared borrow flag is held by the returned reference.
+        Ok(ElementRef::from_flag(unsafe { &*packed.value.get() }, flag))
+    }
+
+    fn lookup_mut(&self, slot: usize) -> ArenaResult<ElementRefMut<'_, T>> {
+        let packed = self.packed(slot).ok_or(ArenaError::RemovedElementAccess)?;
+        let flag = packed.flag.try_borrow_mut().ok_or(BorrowError::ElementAlreadyBorrowed)?;
+
+        // SAFETY: the exclusive borrow flag is held by the returned reference.
+        Ok(ElementRefMut::from_flag(unsafe { &mut *packed.value.get() }, flag))
+    }
+
+    fn is_occupied(&self, slot: usize) -> bool {
+        self.sparse
+            .get(slot)
+            .is_some_and(|sparse_slot| sparse_slot.position.is_some())
+    }
+
+    fn generation(&self, slot: usize) -> u32 {
+        self.sparse.get(slot).map_or(0, |sparse_slot| sparse_slot.generation)
+    }
+
+    fn occupied(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(self.dense.iter().map(|packed| packed.slot))
+    }
+
+    fn vacant(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(self.free.iter().rev().copied())
+    }
+}
+
+impl<T> Arena<T, SparseSetStorage<T>> {
+    pub fn sparse_set() -> Self {
+        Self::with_storage(SparseSetStorage::new())
+    }
+}
+
+impl<T> Default for SparseSetStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> TryClone for SparseSetStorage<T> {
+    fn try_clone(&self) -> ArenaResult<Self> {
+        let dense = self
+            .dense
+            .iter()
+            .map(|packed| {
+                let element = self.lookup(packed.slot)?;
+
+                Ok(Packed {
+                    slot: packed.slot,
+                    flag: BorrowFlag::default(),
+                    value: UnsafeCell::new(element.clone()),
+                })
+            })
+            .collect::<ArenaResult<_>>()?;
+
+        Ok(Self { dense, sparse: self.sparse.clone(), free: self.free.clone() })
+    }
+}
+
+// Panics if an element is borrowed mutably, see `try_clone`.
+impl<T: Clone> Clone for SparseSetStorage<T> {
+    fn clone(&self) -> Self {
+        self.try_clone().expect("failed to borrow element for cloning")
+    }
+}
+
+impl<T> fmt::Debug for SparseSetStorage<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SparseSetStorage")
+            .field("slots", &self.dense.iter().map(|packed| packed.slot).collect::<Vec<_>>())
+            .field("free", &self.free)
+            .finish()
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{ArenaError, ArenaResult, ElementRef, ElementRefMut};
+
+use vec_cell::{Flatten, VecCell};
+
+// Backends hand out slots addressed by `usize`. A slot's generation is bumped whenever its element
+// is taken, and vacant slots may be reused by later insertions.
+pub trait Storage<T> {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Every slot handed out so far is below the capacity, whether it's occupied or not.
+    fn capacity(&self) -> usize;
+
+    // The slot which will be used by the next `insert`.
+    fn next_slot(&self) -> usize;
+
+    fn insert(&mut self, value: T) -> usize;
+    // Inserts into a vacant slot, or appends a new one if `slot` is the capacity. Panics if the
+    // slot is occupied or past the capacity.
+    fn insert_at(&mut self, slot: usize, value: T);
+    fn take(&mut self, slot: usize) -> Option<T>;
+
+    fn lookup(&self, slot: usize) -> ArenaResult<ElementRef<'_, T>>;
+    fn lookup_mut(&self, slot: usize) -> ArenaResult<ElementRefMut<'_, T>>;
+
+    // Borrowed slots always count as occupied.
+    fn is_occupied(&self, slot: usize) -> bool;
+    fn generation(&self, slot: usize) -> u32;
+
+    fn occupied(&self) -> Box<dyn Iterator<Item = usize> + '_>;
+
+    // Vacant slots in the order later insertions reuse them, so snapshots can preserve it.
+    fn vacant(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new((0..self.capacity()).filter(move |slot| !self.is_occupied(*slot)))
+    }
+}
+
+// Cloning through `&self` fails if an element is borrowed mutably, so `Clone` impls of arenas and
+// storages panic in that case and this is the fallible alternative.
+pub trait TryClone: Sized {
+    fn try_clone(&self) -> ArenaResult<Self>;
+}
+
+pub type DefaultStorage<T> = DenseStorage<T>;
+
+// Keeps all elements in one `VecCell`, which is reallocated when the arena grows.
+#[derive(Debug)]
+pub struct DenseStorage<T> {
+    cells: VecCell<Option<T>>,
+    generations: Vec<u32>,
+    free: Vec<usize>,
+}
+
+impl<T> DenseStorage<T> {
+    pub fn new() -> Self {
+        Self { cells: VecCell::new(), generations: vec![], free: vec![] }
+    }
+
+    // Slots which are `None` are vacant, `vacant` lists them in the order in which they're reused.
+    pub(crate) fn from_slots(
+        slots: Vec<Option<T>>,
+        generations: Vec<u32>,
+        vacant: Vec<usize>,
+    ) -> Self {
+        let mut cells = VecCell::new();
+        slots.into_iter().for_each(|element| cells.push(element));
+
+        Self { cells, generations, free: vacant.into_iter().rev().collect() }
+    }
+}
+
+impl<T> Storage<T> for DenseStorage<T> {
+    fn len(&self) -> usize {
+        self.cells.len() - self.free.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn next_slot(&self) -> usize {
+        self.free.last().copied().unwrap_or_else(|| self.cells.len())
+    }
+
+    fn insert(&mut self, value: T) -> usize {
+        match self.free.pop() {
+            Some(slot) => {
+                *self.cells.try_borrow_mut(slot).unwrap() = Some(value);
+
+                slot
+            }
+            None => {
+                self.cells.push(Some(value));
+                self.generations.push(0);
+
+                self.cells.len() - 1
+            }
+        }
+    }
+
+    fn insert_at(&mut self, slot: usize, value: T) {
+        if slot == self.cells.len() {
+            self.cells.push(Some(value));
+            self.generations.push(0);
+
+            return;
+        }
+
+        let position = self.free.iter().rposition(|&free| free == slot).expect("slot is occupied");
+        self.free.remove(position);
+        *self.cells.try_borrow_mut(slot).unwrap() = Some(value);
+    }
+
+    fn take(&mut self, slot: usize) -> Option<T> {
+        let element = self.cells.try_take(slot).ok().flatten()?;
+        self.generations[slot] = self.generations[slot].wrapping_add(1);
+        self.free.push(slot);
+
+        Some(element)
+    }
+
+    fn lookup(&self, slot: usize) -> ArenaResult<ElementRef<'_, T>> {
+        self.cells
+            .try_borrow(slot)
+            .flatten()
+            .map(ElementRef::from)
+            .map_err(ArenaError::from)
+    }
+
+    fn lookup_mut(&self, slot: usize) -> ArenaResult<ElementRefMut<'_, T>> {
+        self.cells
+            .try_borrow_mut(slot)
+            .flatten()
+            .map(ElementRefMut::from)
+            .map_err(ArenaError::from)
+    }
+
+    // A slot can only be borrowed through `lookup` or `lookup_mut`, which fail for vacant slots,
+    // so a borrowed slot is always occupied.
+    fn is_occupied(&self, slot: usize) -> bool {
+        slot < self.cells.len()
+            && self.cells.try_borrow(slot).map_or(true, |element| element.is_some())
+    }
+
+    fn generation(&self, slot: usize) -> u32 {
+        self.generations.get(slot).copied().unwrap_or(0)
+    }
+
+    fn occupied(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new((0..self.cells.len()).filter(|&slot| self.is_occupied(slot)))
+    }
+
+    fn vacant(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(self.free.iter().rev().copied())
+    }
+}
+
+impl<T> Default for DenseStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> TryClone for DenseStorage<T> {
+    fn try_clone(&self) -> ArenaResult<Self> {
+        let mut cells = VecCell::new();
+        (0..self.cells.len()).try_for_each(|slot| {
+            cells.push(self.cells.try_borrow(slot)?.clone());
+
+            Ok::<_, ArenaError>(())
+        })?;
+
+        Ok(Self { cells, generations: self.generations.clone(), free: self.free.clone() })
+    }
+}
+
+// Panics if an element is borrowed mutably, see `try_clone`.
+impl<T: Clone> Clone for DenseStorage<T> {
+    fn clone(&self) -> Self {
+        self.try_clone().expect("failed to borrow element for cloning")
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{Arena, ArenaResult, ElementRef, ElementRefMut, ErasedArena, Handle, Handleable};
+use crate::{Index, References};
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::{cmp, fmt};
+
+#[derive(Debug, Default)]
+pub struct ArenaSystem {
+    arenas: RefCell<HashMap<TypeId, Box<dyn ErasedArena>>>,
+}
+
+impl ArenaSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn arena<T: 'static>(&self) -> &Arena<T> {
+        let mut arenas = self.arenas.borrow_mut();
+        let arena = arenas
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Arena::<T>::new()));
+        let arena = arena.downcast_ref::<T>().unwrap() as *const Arena<T>;
+
+        // SAFETY: arenas are boxed and are only removed or replaced through `&mut self`, so the
+        // reference stays valid for as long as the system is borrowed.
+        unsafe { &*arena }
+    }
+
+    pub fn arena_mut<T: 'static>(&mut self) -> &mut Arena<T> {
+        self.arenas
+            .get_mut()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Arena::<T>::new()))
+            .downcast_mut::<T>()
+            .unwrap()
+    }
+
+    pub fn erased_arenas(&self) -> Vec<&dyn ErasedArena> {
+        self.arenas
+            .borrow()
+            .values()
+            .map(|arena| {
+                let arena = &**arena as *const dyn ErasedArena;
+
+                // SAFETY: see `arena`.
+                unsafe { &*arena }
+            })
+            .collect()
+    }
+
+    pub fn erased_arenas_mut(&mut self) -> impl Iterator<Item = &mut dyn ErasedArena> {
+        self.arenas.get_mut().values_mut().map(|arena| &mut **arena)
+    }
+
+    pub fn has_arena<T: 'static>(&self) -> bool {
+        self.arenas.borrow().contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn add<T: 'static>(&mut self, value: T) -> Index {
+        self.arena_mut::<T>().add(value)
+    }
+
+    pub fn remove<T: 'static>(&mut self, index: Index) -> ArenaResult<T> {
+        self.arena_mut::<T>().remove(index)
+    }
+
+    pub fn lookup<T: 'static>(&self, index: Index) -> ArenaResult<ElementRef<'_, T>> {
+        self.arena::<T>().lookup(index)
+    }
+
+    pub fn lookup_mut<T: 'static>(&self, index: Index) -> ArenaResult<ElementRefMut<'_, T>> {
+        self.arena::<T>().lookup_mut(index)
+    }
+
+    pub fn handle<'arena, T: Handleable<'arena> + 'static>(
+        &'arena self,
+        index: Index,
+        userdata: <T::Handle as Handle<'arena>>::Userdata,
+    ) -> T::Handle {
+        self.arena::<T>().handle(index, userdata)
+    }
+
+    pub fn system_handle<T: 'static>(&self, index: Index) -> SystemHandle<'_, T> {
+        SystemHandle::new(self, index)
+    }
+}
+
+pub struct SystemHandle<'system, T> {
+    system: &'system ArenaSystem,
+    index: Index,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'system, T: 'static> SystemHandle<'system, T> {
+    fn new(system: &'system ArenaSystem, index: Index) -> Self {
+        Self { system, index, _marker: PhantomData }
+    }
+
+    pub fn get(&self) -> ArenaResult<ElementRef<'system, T>> {
+        self.system.lookup(self.index)
+    }
+
+    pub fn get_mut(&self) -> ArenaResult<ElementRefMut<'system, T>> {
+        self.system.lookup_mut(self.index)
+    }
+
+    pub fn exists(&self) -> bool {
+        self.system.arena::<T>().contains(self.index)
+    }
+
+    pub fn system(&self) -> &'system ArenaSystem {
+        self.system
+    }
+
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    pub fn handle(&self, userdata: <T::Handle as Handle<'system>>::Userdata) -> T::Handle
+    where
+        T: Handleable<'system>,
+    {
+        self.system.handle::<T>(self.index, userdata)
+    }
+
+    pub fn follow<U: 'static>(
+        &self,
+        f: impl FnOnce(&T) -> Index,
+    ) -> Option<SystemHandle<'system, U>> {
+        let index = f(&*self.get().ok()?);
+
+        (!index.is_invalid()).then(|| SystemHandle::new(self.system, index))
+    }
+
+    pub fn references<U: 'static>(&self) -> Vec<SystemHandle<'system, U>>
+    where
+        T: References<U>,
+    {
+        let mut handles = vec![];
+        if let Ok(element) = self.get() {
+            element.visit_references(&mut |_, index| {
+                handles.push(SystemHandle::new(self.system, index));
+            });
+        }
+
+        handles
+    }
+}
+
+impl<T> fmt::Debug for SystemHandle<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("SystemHandle({})", <Index as Into<i64>>::into(self.index)))
+    }
+}
+
+impl<T> Clone for SystemHandle<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SystemHandle<'_, T> {}
+
+impl<T> cmp::PartialEq for SystemHandle<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> cmp::Eq for SystemHandle<'_, T> {}
@@ -0,0 +1,511 @@
+use crate::{Arena, ArenaError, ArenaResult, ElementRef, ElementRefMut, Handle, Handleable, Index};
+
+use std::collections::VecDeque;
+use std::{fmt, iter, ops};
+
+#[derive(Debug, Clone, Copy, Default)]
+struct TreeLinks {
+    parent: Option<Index>,
+    first_child: Option<Index>,
+    last_child: Option<Index>,
+    prev_sibling: Option<Index>,
+    next_sibling: Option<Index>,
+}
+
+// Links are kept next to the arena, slot by slot, so that elements stay plain `T` and can be
+// handled by the handles generated for them.
+#[derive(Debug, Clone)]
+pub struct TreeArena<T> {
+    arena: Arena<T>,
+    links: Vec<Option<TreeLinks>>,
+}
+
+impl<T> TreeArena<T> {
+    pub fn new() -> Self {
+        Self { arena: Arena::new(), links: vec![] }
+    }
+
+    pub fn arena(&self) -> &Arena<T> {
+        &self.arena
+    }
+
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        slot(&self.links, index).is_some()
+    }
+
+    // New elements are roots until they are linked into a tree.
+    pub fn add(&mut self, value: T) -> Index {
+        let index = self.arena.add(value);
+        let slot: usize = index.into();
+        if slot == self.links.len() {
+            self.links.push(Some(TreeLinks::default()));
+        } else {
+            self.links[slot] = Some(TreeLinks::default());
+        }
+
+        index
+    }
+
+    pub fn lookup(&self, index: Index) -> ArenaResult<ElementRef<'_, T>> {
+        self.arena.lookup(index)
+    }
+
+    pub fn lookup_mut(&self, index: Index) -> ArenaResult<ElementRefMut<'_, T>> {
+        self.arena.lookup_mut(index)
+    }
+
+    pub fn parent(&self, index: Index) -> Option<Index> {
+        slot(&self.links, index).and_then(|links| links.parent)
+    }
+
+    pub fn first_child(&self, index: Index) -> Option<Index> {
+        slot(&self.links, index).and_then(|links| links.first_child)
+    }
+
+    pub fn last_child(&self, index: Index) -> Option<Index> {
+        slot(&self.links, index).and_then(|links| links.last_child)
+    }
+
+    pub fn prev_sibling(&self, index: Index) -> Option<Index> {
+        slot(&self.links, index).and_then(|links| links.prev_sibling)
+    }
+
+    pub fn next_sibling(&self, index: Index) -> Option<Index> {
+        slot(&self.links, index).and_then(|links| links.next_sibling)
+    }
+
+    pub fn is_root(&self, index: Index) -> bool {
+        self.contains(index) && self.parent(index).is_none()
+    }
+
+    pub fn roots(&self) -> impl Iterator<Item = Index> + '_ {
+        self.links.iter().enumerate().filter_map(|(i, links)| match links {
+            Some(TreeLinks { parent: None, .. }) => Some(Index::from(i)),
+            _ => None,
+        })
+    }
+
+    pub fn append(&mut self, parent: Index, child: Index) -> ArenaResult<()> {
+        self.check_move(parent, child)?;
+        self.unlink(child);
+
+        let last_child = self.links_mut(parent).last_child;
+        match last_child {
+            Some(last_child) => self.links_mut(last_child).next_sibling = Some(child),
+            None => self.links_mut(parent).first_child = Some(child),
+        }
+        self.links_mut(parent).last_child = Some(child);
+
+        let links = self.links_mut(child);
+        links.parent = Some(parent);
+        links.prev_sibling = last_child;
+
+        Ok(())
+    }
+
+    pub fn prepend(&mut self, parent: Index, child: Index) -> ArenaResult<()> {
+        self.check_move(parent, child)?;
+        self.unlink(child);
+
+        let first_child = self.links_mut(parent).first_child;
+        match first_child {
+            Some(first_child) => self.links_mut(first_child).prev_sibling = Some(child),
+            None => self.links_mut(parent).last_child = Some(child),
+        }
+        self.links_mut(parent).first_child = Some(child);
+
+        let links = self.links_mut(child);
+        links.parent = Some(parent);
+        links.next_sibling = first_child;
+
+        Ok(())
+    }
+
+    pub fn insert_before(&mut self, sibling: Index, node: Index) -> ArenaResult<()> {
+        let parent = self.links(sibling)?.parent.ok_or(ArenaError::RootSibling)?;
+        if sibling == node {
+            return self.links(node).map(|_| ());
+        }
+
+        self.check_move(parent, node)?;
+        self.unlink(node);
+
+        let prev_sibling = self.links_mut(sibling).prev_sibling;
+        match prev_sibling {
+            Some(prev_sibling) => self.links_mut(prev_sibling).next_sibling = Some(node),
+            None => self.links_mut(parent).first_child = Some(node),
+        }
+        self.links_mut(sibling).prev_sibling = Some(node);
+
+        let links = self.links_mut(node);
+        links.parent = Some(parent);
+        links.prev_sibling = prev_sibling;
+        links.next_sibling = Some(sibling);
+
+        Ok(())
+    }
+
+    pub fn insert_after(&mut self, sibling: Index, node: Index) -> ArenaResult<()> {
+        let parent = self.links(sibling)?.parent.ok_or(ArenaError::RootSibling)?;
+        if sibling == node {
+            return self.links(node).map(|_| ());
+        }
+
+        self.check_move(parent, node)?;
+        self.unlink(node);
+
+        let next_sibling = self.links_mut(sibling).next_sibling;
+        match next_sibling {
+            Some(next_sibling) => self.links_mut(next_sibling).prev_sibling = Some(node),
+            None => self.links_mut(parent).last_child = Some(node),
+        }
+        self.links_mut(sibling).next_sibling = Some(node);
+
+        let links = self.links_mut(node);
+        links.parent = Some(parent);
+        links.prev_sibling = Some(sibling);
+        links.next_sibling = next_sibling;
+
+        Ok(())
+    }
+
+    // Makes `node` a root, keeping its subtree attached to it.
+    pub fn detach(&mut self, node: Index) -> ArenaResult<()> {
+        self.links(node)?;
+        self.unlink(node);
+
+        Ok(())
+    }
+
+    // Returns removed elements in pre-order.
+    pub fn remove_subtree(&mut self, node: Index) -> ArenaResult<Vec<T>> {
+        self.links(node)?;
+        self.unlink(node);
+
+        let subtree = self.pre_order(node).collect::<Vec<_>>();
+        subtree
+            .into_iter()
+            .map(|index| {
+                self.links[<Index as Into<usize>>::into(index)] = None;
+                self.arena.remove(index)
+            })
+            .collect()
+    }
+
+    pub fn depth(&self, node: Index) -> usize {
+        self.ancestors(node).count()
+    }
+
+    pub fn children(&self, node: Index) -> Children<'_> {
+        Children { links: &self.links, next: self.first_child(node) }
+    }
+
+    pub fn ancestors(&self, node: Index) -> Ancestors<'_> {
+        Ancestors { links: &self.links, next: self.parent(node) }
+    }
+
+    pub fn pre_order(&self, node: Index) -> PreOrder<'_> {
+        PreOrder { links: &self.links, root: node, next: self.contains(node).then_some(node) }
+    }
+
+    pub fn post_order(&self, node: Index) -> PostOrder<'_> {
+        let next = self.contains(node).then(|| leftmost_leaf(&self.links, node));
+
+        PostOrder { links: &self.links, root: node, next }
+    }
+
+    pub fn breadth_first(&self, node: Index) -> BreadthFirst<'_> {
+        BreadthFirst {
+            links: &self.links,
+            queue: self.contains(node).then_some(node).into_iter().collect(),
+        }
+    }
+
+    fn links(&self, index: Index) -> ArenaResult<&TreeLinks> {
+        if index.is_invalid() {
+            return Err(ArenaError::InvalidIndexUsage);
+        }
+
+        slot(&self.links, index).ok_or(ArenaError::RemovedElementAccess)
+    }
+
+    fn links_mut(&mut self, index: Index) -> &mut TreeLinks {
+        self.links[<Index as Into<usize>>::into(index)]
+            .as_mut()
+            .expect("tree links of removed element")
+    }
+
+    fn check_move(&self, parent: Index, node: Index) -> ArenaResult<()> {
+        self.links(parent)?;
+        self.links(node)?;
+
+        if parent == node || self.ancestors(parent).any(|ancestor| ancestor == node) {
+            return Err(ArenaError::TreeCycle);
+        }
+
+        Ok(())
+    }
+
+    fn unlink(&mut self, node: Index) {
+        let TreeLinks { parent, prev_sibling, next_sibling, .. } = *self.links_mut(node);
+
+        match prev_sibling {
+            Some(prev_sibling) => self.links_mut(prev_sibling).next_sibling = next_sibling,
+            None => {
+                if let Some(parent) = parent {
+                    self.links_mut(parent).first_child = next_sibling;
+                }
+            }
+        }
+
+        match next_sibling {
+            Some(next_sibling) => self.links_mut(next_sibling).prev_sibling = prev_sibling,
+            None => {
+                if let Some(parent) = parent {
+                    self.links_mut(parent).last_child = prev_sibling;
+                }
+            }
+        }
+
+        let links = self.links_mut(node);
+        links.parent = None;
+        links.prev_sibling = None;
+        links.next_sibling = None;
+    }
+}
+
+impl<'arena, T: Handleable<'arena>> TreeArena<T> {
+    pub fn handle(
+        &'arena self,
+        index: Index,
+        userdata: <T::Handle as Handle<'arena>>::Userdata,
+    ) -> TreeHandle<'arena, T> {
+        TreeHandle { tree: self, handle: self.arena.handle(index, userdata.clone()), userdata }
+    }
+}
+
+impl<T> Default for TreeArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn slot(links: &[Option<TreeLinks>], index: Index) -> Option<&TreeLinks> {
+    if index.is_invalid() {
+        return None;
+    }
+
+    links.get(<Index as Into<usize>>::into(index)).and_then(Option::as_ref)
+}
+
+fn leftmost_leaf(links: &[Option<TreeLinks>], mut node: Index) -> Index {
+    while let Some(first_child) = slot(links, node).and_then(|links| links.first_child) {
+        node = first_child;
+    }
+
+    node
+}
+
+pub struct Children<'tree> {
+    links: &'tree [Option<TreeLinks>],
+    next: Option<Index>,
+}
+
+impl iter::Iterator for Children<'_> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = slot(self.links, current).and_then(|links| links.next_sibling);
+
+        Some(current)
+    }
+}
+
+pub struct Ancestors<'tree> {
+    links: &'tree [Option<TreeLinks>],
+    next: Option<Index>,
+}
+
+impl iter::Iterator for Ancestors<'_> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = slot(self.links, current).and_then(|links| links.parent);
+
+        Some(current)
+    }
+}
+
+pub struct PreOrder<'tree> {
+    links: &'tree [Option<TreeLinks>],
+    root: Index,
+    next: Option<Index>,
+}
+
+impl iter::Iterator for PreOrder<'_> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+
+        self.next = slot(self.links, current).and_then(|links| links.first_child);
+        let mut node = current;
+        while self.next.is_none() && node != self.root {
+            match slot(self.links, node) {
+                Some(TreeLinks { next_sibling, parent: Some(parent), .. }) => {
+                    self.next = *next_sibling;
+                    node = *parent;
+                }
+                _ => break,
+            }
+        }
+
+        Some(current)
+    }
+}
+
+pub struct PostOrder<'tree> {
+    links: &'tree [Option<TreeLinks>],
+    root: Index,
+    next: Option<Index>,
+}
+
+impl iter::Iterator for PostOrder<'_> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+
+        self.next = match slot(self.links, current) {
+            Some(_) if current == self.root => None,
+            Some(TreeLinks { next_sibling: Some(next_sibling), .. }) => {
+                Some(leftmost_leaf(self.links, *next_sibling))
+            }
+            Some(links) => links.parent,
+            None => None,
+        };
+
+        Some(current)
+    }
+}
+
+pub struct BreadthFirst<'tree> {
+    links: &'tree [Option<TreeLinks>],
+    queue: VecDeque<Index>,
+}
+
+impl iter::Iterator for BreadthFirst<'_> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.queue.pop_front()?;
+        self.queue.extend(Children {
+            links: self.links,
+            next: slot(self.links, current).and_then(|links| links.first_child),
+        });
+
+        Some(current)
+    }
+}
+
+pub struct TreeHandle<'arena, T: Handleable<'arena>> {
+    tree: &'arena TreeArena<T>,
+    handle: T::Handle,
+    userdata: <T::Handle as Handle<'arena>>::Userdata,
+}
+
+impl<'arena, T: Handleable<'arena>> TreeHandle<'arena, T> {
+    pub fn tree(&self) -> &'arena TreeArena<T> {
+        self.tree
+    }
+
+    pub fn handle(&self) -> &T::Handle {
+        &self.handle
+    }
+
+    pub fn into_handle(self) -> T::Handle {
+        self.handle
+    }
+
+    pub fn parent(&self) -> Option<Self> {
+        self.tree.parent(self.index()).map(|index| self.related_handle(index))
+    }
+
+    pub fn first_child(&self) -> Option<Self> {
+        self.tree.first_child(self.index()).map(|index| self.related_handle(index))
+    }
+
+    pub fn last_child(&self) -> Option<Self> {
+        self.tree.last_child(self.index()).map(|index| self.related_handle(index))
+    }
+
+    pub fn prev_sibling(&self) -> Option<Self> {
+        self.tree
+            .prev_sibling(self.index())
+            .map(|index| self.related_handle(index))
+    }
+
+    pub fn next_sibling(&self) -> Option<Self> {
+        self.tree
+            .next_sibling(self.index())
+            .map(|index| self.related_handle(index))
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.tree.is_root(self.index())
+    }
+
+    pub fn depth(&self) -> usize {
+        self.tree.depth(self.index())
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = Self> + '_ {
+        self.tree.children(self.index()).map(|index| self.related_handle(index))
+    }
+
+    pub fn ancestors(&self) -> impl Iterator<Item = Self> + '_ {
+        self.tree.ancestors(self.index()).map(|index| self.related_handle(index))
+    }
+
+    pub fn pre_order(&self) -> impl Iterator<Item = Self> + '_ {
+        self.tree.pre_order(self.index()).map(|index| self.related_handle(index))
+    }
+
+    pub fn post_order(&self) -> impl Iterator<Item = Self> + '_ {
+        self.tree.post_order(self.index()).map(|index| self.related_handle(index))
+    }
+
+    pub fn breadth_first(&self) -> impl Iterator<Item = Self> + '_ {
+        self.tree
+            .breadth_first(self.index())
+            .map(|index| self.related_handle(index))
+    }
+
+    fn related_handle(&self, index: Index) -> Self {
+        self.tree.handle(index, self.userdata.clone())
+    }
+}
+
+impl<'arena, T: Handleable<'arena>> ops::Deref for TreeHandle<'arena, T> {
+    type Target = T::Handle;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+impl<'arena, T: Handleable<'arena>> fmt::Debug for TreeHandle<'arena, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("TreeHandle({})", <Index as Into<i64>>::into(self.index())))
+    }
+}
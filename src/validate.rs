@@ -0,0 +1,110 @@
+use crate::{Arena, Index, References, Storage};
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DanglingKind {
+    Invalid,
+    OutOfBounds,
+    Removed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DanglingReference {
+    pub owner: Index,
+    pub field: &'static str,
+    pub target: Index,
+    pub kind: DanglingKind,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    dangling: Vec<DanglingReference>,
+    uninspected: Vec<Index>,
+}
+
+impl ValidationReport {
+    // Elements which couldn't be inspected may hold dangling references, so they make the report
+    // invalid as well.
+    pub fn is_valid(&self) -> bool {
+        self.dangling.is_empty() && self.uninspected.is_empty()
+    }
+
+    pub fn dangling(&self) -> &[DanglingReference] {
+        &self.dangling
+    }
+
+    // Elements which were borrowed mutably during validation.
+    pub fn uninspected(&self) -> &[Index] {
+        &self.uninspected
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return f.write_str("no dangling references");
+        }
+
+        self.dangling.iter().try_for_each(|reference| {
+            let owner: i64 = reference.owner.into();
+            let target: i64 = reference.target.into();
+            let reason = match reference.kind {
+                DanglingKind::Invalid => "invalid index",
+                DanglingKind::OutOfBounds => "out of bounds",
+                DanglingKind::Removed => "removed element",
+            };
+
+            writeln!(f, "element {owner}, field `{}`: {target} ({reason})", reference.field)
+        })?;
+
+        self.uninspected.iter().try_for_each(|index| {
+            let index: i64 = (*index).into();
+
+            writeln!(f, "element {index}: couldn't be inspected (borrowed mutably)")
+        })
+    }
+}
+
+impl<T, S: ?Sized + Storage<T>> Arena<T, S> {
+    pub fn validate(&self) -> ValidationReport
+    where
+        T: References,
+    {
+        self.validate_against(self)
+    }
+
+    pub fn validate_against<U, R>(&self, target: &Arena<U, R>) -> ValidationReport
+    where
+        T: References<U>,
+        R: ?Sized + Storage<U>,
+    {
+        let mut report = ValidationReport::default();
+
+        self.indices().for_each(|owner| {
+            let Ok(element) = self.lookup(owner) else {
+                report.uninspected.push(owner);
+
+                return;
+            };
+
+            element.visit_references(&mut |field, index| {
+                let kind = if index.is_invalid() {
+                    DanglingKind::Invalid
+                } else if <Index as Into<usize>>::into(index) >= target.capacity() {
+                    DanglingKind::OutOfBounds
+                } else if !target.contains(index) {
+                    DanglingKind::Removed
+                } else {
+                    return;
+                };
+
+                report
+                    .dangling
+                    .push(DanglingReference { owner, field, target: index, kind });
+            });
+        });
+
+        report
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{Arena, ArenaResult, ArenaSystem, Handle, Handleable, Index};
+
+use std::any::Any;
+
+pub trait WorldArena<T> {
+    fn world_arena(&self) -> &Arena<T>;
+    fn world_arena_mut(&mut self) -> &mut Arena<T>;
+}
+
+pub trait World {
+    fn arena<T>(&self) -> &Arena<T>
+    where
+        Self: WorldArena<T>,
+    {
+        self.world_arena()
+    }
+
+    fn arena_mut<T>(&mut self) -> &mut Arena<T>
+    where
+        Self: WorldArena<T>,
+    {
+        self.world_arena_mut()
+    }
+
+    fn add<T>(&mut self, value: T) -> Index
+    where
+        Self: WorldArena<T>,
+    {
+        self.world_arena_mut().add(value)
+    }
+
+    // Unlike removing straight from the arena, this also removes the element's relation links.
+    fn remove<T: 'static>(&mut self, index: Index) -> ArenaResult<T>
+    where
+        Self: WorldArena<T>,
+    {
+        let element = self.world_arena().detach(index)?;
+        let value = self.world_arena_mut().remove(index)?;
+        self.remove_links(&element);
+
+        Ok(value)
+    }
+
+    // `element` is the `DetachedHandle` of the removed element.
+    fn remove_links(&mut self, _element: &dyn Any) {}
+
+    fn handle<'arena, T>(&'arena self, index: Index) -> T::Handle
+    where
+        Self: WorldArena<T>,
+        T: Handleable<'arena> + 'arena,
+        T::Handle: Handle<'arena, Userdata = &'arena Self>,
+    {
+        self.world_arena().handle(index, self)
+    }
+}
+
+impl World for ArenaSystem {}
+
+impl<T: 'static> WorldArena<T> for ArenaSystem {
+    fn world_arena(&self) -> &Arena<T> {
+        ArenaSystem::arena(self)
+    }
+
+    fn world_arena_mut(&mut self) -> &mut Arena<T> {
+        ArenaSystem::arena_mut(self)
+    }
+}
+
+#[macro_export]
+macro_rules! arena_world {
+    (
+        $( #[$attr:meta] )*
+        $vis:vis struct $name:ident {
+            $( $field_vis:vis $field:ident : $ty:ty ),* $(,)?
+        }
+        $(
+            relations {
+                $( $relation_vis:vis $relation:ident : $relation_ty:ty $( = $init:expr )? ),*
+                $(,)?
+            }
+        )?
+    ) => {
+        $( #[$attr] )*
+        $vis struct $name {
+            $( $field_vis $field: $crate::Arena<$ty>, )*
+            $( $( $relation_vis $relation: $relation_ty, )* )?
+        }
+
+        impl $name {
+            #[allow(dead_code)]
+            $vis fn new() -> Self {
+                Self {
+                    $( $field: $crate::Arena::new(), )*
+                    $( $( $relation: $crate::arena_world!(@init $( $init )?), )* )?
+                }
+            }
+
+            $(
+                #[allow(dead_code)]
+                $vis fn $field(&self) -> &$crate::Arena<$ty> {
+                    &self.$field
+                }
+            )*
+
+            $( $(
+                #[allow(dead_code)]
+                $vis fn $relation(&self) -> &$relation_ty {
+                    &self.$relation
+                }
+            )* )?
+        }
+
+        impl ::std::default::Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $crate::World for $name {
+            #[allow(unused_variables)]
+            fn remove_links(&mut self, element: &dyn ::std::any::Any) {
+                $( $(
+                    $crate::RelationStore::remove_links(&mut self.$relation, element);
+                )* )?
+            }
+        }
+
+        $(
+            impl $crate::WorldArena<$ty> for $name {
+                fn world_arena(&self) -> &$crate::Arena<$ty> {
+                    &self.$field
+                }
+
+                fn world_arena_mut(&mut self) -> &mut $crate::Arena<$ty> {
+                    &mut self.$field
+                }
+            }
+        )*
+    };
+    (@init) => {
+        ::std::default::Default::default()
+    };
+    (@init $init:expr) => {
+        $init
+    };
+}
@@ -0,0 +1,33 @@
+use arena_system::{Arena, ArenaError};
+use arena_system_proc_macro::Handleable;
+
+#[derive(Handleable, Debug)]
+struct Foo {
+    #[handle_getter(return_type(copy))]
+    value: u32,
+}
+
+#[test]
+fn attach_checks_arena_and_generation() {
+    let mut arena = Arena::new();
+    arena.add(Foo { value: 1 });
+    let index = arena.add(Foo { value: 2 });
+    let detached = arena.detach(index).unwrap();
+    assert_eq!(detached.attach(&arena).unwrap().value(), Some(2));
+
+    let other = Arena::from(vec![Foo { value: 0 }, Foo { value: 0 }]);
+    assert!(matches!(detached.attach(&other), Err(ArenaError::ForeignIndex)));
+
+    arena.remove(index).unwrap();
+    assert!(matches!(detached.attach(&arena), Err(ArenaError::StaleIndex)));
+
+    // The slot is reused, but the handle still refers to the removed element.
+    let reused = arena.add(Foo { value: 3 });
+    assert_eq!(reused, index);
+    assert!(matches!(detached.attach(&arena), Err(ArenaError::StaleIndex)));
+    assert!(!detached.is_live(&arena));
+
+    let current = arena.detach(reused).unwrap();
+    assert_ne!(current, detached);
+    assert_eq!(current.attach(&arena).unwrap().value(), Some(3));
+}
@@ -0,0 +1,56 @@
+use arena_system::{Arena, ArenaSystem, ErasedArena, Index};
+
+use std::any::TypeId;
+
+#[derive(Debug)]
+struct Position(u8);
+
+struct Tag;
+
+#[test]
+fn erased_arenas_downcast_to_their_element_type() {
+    let mut positions = Arena::from(vec![Position(1), Position(2)]);
+    positions.enable_element_debug();
+    let tags = Arena::from(vec![Tag]);
+    let mut arenas: Vec<Box<dyn ErasedArena>> = vec![Box::new(positions), Box::new(tags)];
+
+    assert_eq!(arenas[0].element_type_id(), TypeId::of::<Position>());
+    assert_eq!(arenas[0].debug_element(Index::new(1)).as_deref(), Some("Position(2)"));
+    // Elements can only be formatted once `enable_element_debug` was called.
+    assert_eq!(arenas[1].debug_element(Index::new(0)), None);
+
+    assert!(arenas[0].downcast_ref::<Tag>().is_none());
+    let positions = arenas[0].downcast_ref::<Position>().unwrap();
+    assert_eq!(positions.lookup(Index::new(0)).unwrap().0, 1);
+    arenas[0].downcast_mut::<Position>().unwrap().add(Position(3));
+    assert_eq!(arenas[0].len(), 3);
+
+    arenas[1].remove(Index::new(0)).unwrap();
+    assert!(!arenas[1].contains(Index::new(0)));
+    assert!(arenas[1].remove(Index::new(0)).is_err());
+    assert!(arenas[1].is_empty());
+}
+
+#[test]
+fn arena_system_exposes_erased_arenas() {
+    let mut system = ArenaSystem::new();
+    system.add(Position(3));
+    system.add(Tag);
+    system.add(Tag);
+
+    let mut arenas = system
+        .erased_arenas()
+        .iter()
+        .map(|arena| (arena.len(), arena.type_name()))
+        .collect::<Vec<_>>();
+    arenas.sort();
+    assert_eq!(arenas.len(), 2);
+    assert_eq!(arenas[0].0, 1);
+    assert!(arenas[0].1.ends_with("Position"));
+
+    system
+        .erased_arenas_mut()
+        .for_each(|arena| arena.remove(Index::new(0)).unwrap());
+    assert_eq!(system.arena::<Tag>().len(), 1);
+    assert!(system.arena::<Position>().is_empty());
+}
@@ -0,0 +1,57 @@
+use arena_system::{Arena, FieldCell};
+use arena_system_proc_macro::Handleable;
+
+#[derive(Handleable, Debug)]
+#[handleable(field_borrows)]
+struct Body {
+    position: FieldCell<(f32, f32)>,
+    #[handle_getter(return_type(copy))]
+    velocity: FieldCell<(f32, f32)>,
+    #[handle_getter(return_type(clone))]
+    name: String,
+}
+
+fn body() -> Body {
+    Body {
+        position: FieldCell::new((0.0, 0.0)),
+        velocity: FieldCell::new((1.0, 2.0)),
+        name: "body".into(),
+    }
+}
+
+#[test]
+fn handles_borrow_different_fields_at_once() {
+    let mut arena = Arena::new();
+    let index = arena.add(body());
+    let mover = arena.handle(index, None);
+    let steerer = arena.handle(index, None);
+
+    let position = mover.position().unwrap();
+    assert!(steerer.set_velocity((3.0, 4.0)));
+    assert_eq!(mover.velocity(), Some((3.0, 4.0)));
+    // The borrowed field and the element as a whole stay locked.
+    assert!(!steerer.set_position((1.0, 1.0)));
+    assert!(!steerer.set_name("other".into()));
+    assert!(arena.lookup_mut(index).is_err());
+    assert_eq!(steerer.name().as_deref(), Some("body"));
+    assert_eq!(*position, (0.0, 0.0));
+    drop(position);
+
+    assert!(steerer.set_position((1.0, 1.0)));
+    assert_eq!(*mover.position().unwrap(), (1.0, 1.0));
+}
+
+#[test]
+fn fields_are_reachable_through_the_element() {
+    let mut arena = Arena::new();
+    let index = arena.add(body());
+    let handle = arena.handle(index, None);
+
+    arena.lookup_mut(index).unwrap().position.get_mut().0 = 5.0;
+    assert_eq!(*handle.position().unwrap(), (5.0, 0.0));
+
+    let element = arena.lookup(index).unwrap();
+    *element.velocity.try_borrow_mut().unwrap() = (7.0, 7.0);
+    assert_eq!(handle.velocity(), Some((7.0, 7.0)));
+    assert!(element.velocity.try_borrow_mut().is_ok());
+}
@@ -0,0 +1,84 @@
+use arena_system::{Arena, ArenaError, ArenaOp, ArenaResult};
+use arena_system::{SnapshotReader, SnapshotValue, SnapshotWriter};
+use arena_system_proc_macro::Handleable;
+
+#[derive(Handleable, Debug)]
+#[handleable(oplog)]
+struct User {
+    #[handle_index(unique)]
+    #[handle_getter(return_type(clone))]
+    name: String,
+    #[handle_index]
+    team: u32,
+}
+
+impl SnapshotValue for User {
+    fn encode(&self, writer: &mut SnapshotWriter) {
+        writer.write(&self.name);
+        writer.write(&self.team);
+    }
+
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+        Ok(Self { name: reader.read()?, team: reader.read()? })
+    }
+}
+
+fn user(name: &str, team: u32) -> User {
+    User { name: name.into(), team }
+}
+
+fn indexed_users() -> Arena<User> {
+    let mut arena: Arena<User> = vec![user("a", 1), user("b", 1), user("c", 2)].into();
+    arena.enable_indexes().unwrap();
+
+    arena
+}
+
+#[test]
+fn add_rejects_duplicate_key() {
+    let mut arena = indexed_users();
+
+    assert!(matches!(arena.try_add(user("a", 3)), Err(ArenaError::DuplicateIndexKey("name"))));
+    assert_eq!(arena.len(), 3);
+    assert!(arena.find_all_by_team(&3).is_empty());
+}
+
+#[test]
+fn setter_rejects_duplicate_key() {
+    let arena = indexed_users();
+    let c = arena.find_by_name(&"c".into()).unwrap();
+
+    let handle = arena.handle(c, None);
+    assert!(!handle.set_name("a".into()));
+    assert_eq!(handle.name().unwrap(), "c");
+    assert_eq!(arena.find_by_name(&"c".into()), Some(c));
+
+    assert!(handle.set_name("z".into()));
+    assert!(handle.set_team(1));
+    assert_eq!(arena.find_by_name(&"z".into()), Some(c));
+    assert_eq!(arena.find_by_name(&"c".into()), None);
+    assert_eq!(arena.find_all_by_team(&1).len(), 3);
+}
+
+#[test]
+fn apply_rejects_duplicate_key() {
+    let mut arena = indexed_users();
+    let a = arena.find_by_name(&"a".into()).unwrap();
+    let c = arena.find_by_name(&"c".into()).unwrap();
+
+    let mut writer = SnapshotWriter::new();
+    writer.write(&"a".to_string());
+    let op = ArenaOp::Set { index: c, field: "name".into(), value: writer.into_bytes() };
+    assert!(matches!(arena.apply(&op), Err(ArenaError::DuplicateIndexKey("name"))));
+    assert_eq!(arena.lookup(c).unwrap().name, "c");
+    assert_eq!(arena.find_by_name(&"a".into()), Some(a));
+    assert_eq!(arena.find_by_name(&"c".into()), Some(c));
+
+    let mut writer = SnapshotWriter::new();
+    writer.write(&user("a", 5));
+    let op = ArenaOp::Replace { index: c, element: writer.into_bytes() };
+    assert!(matches!(arena.apply(&op), Err(ArenaError::DuplicateIndexKey("name"))));
+    assert_eq!(arena.find_by_name(&"c".into()), Some(c));
+    assert_eq!(arena.find_all_by_team(&2), [c]);
+    assert!(arena.find_all_by_team(&5).is_empty());
+}
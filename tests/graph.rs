@@ -0,0 +1,64 @@
+use arena_system::{Arena, ArenaError, Index};
+use arena_system_proc_macro::References;
+
+#[derive(References, Debug)]
+struct Task {
+    #[reference]
+    dependencies: Vec<Index>,
+}
+
+fn graph(edges: &[&[i64]]) -> Arena<Task> {
+    edges
+        .iter()
+        .map(|targets| Task { dependencies: targets.iter().copied().map(Index::new).collect() })
+        .collect()
+}
+
+fn indices(indices: Vec<Index>) -> Vec<i64> {
+    indices.into_iter().map(Into::into).collect()
+}
+
+#[test]
+fn topological_sort_orders_referencing_elements_first() {
+    // Dangling references of the last task aren't edges.
+    let arena = graph(&[&[1, 2], &[3], &[3, 4], &[5], &[], &[9, -1]]);
+    let order = indices(arena.topological_sort().unwrap());
+    assert_eq!(order.len(), 6);
+    arena.indices().for_each(|index| {
+        let position = |index: Index| order.iter().position(|i| *i == index.into()).unwrap();
+        arena
+            .successors(index)
+            .unwrap()
+            .into_iter()
+            .for_each(|successor| assert!(position(index) < position(successor)));
+    });
+    assert!(!arena.has_cycle().unwrap());
+
+    assert_eq!(indices(arena.bfs(Index::new(0)).unwrap()), [0, 1, 2, 3, 4, 5]);
+    assert_eq!(
+        indices(arena.shortest_path(Index::new(0), Index::new(5)).unwrap().unwrap()),
+        [0, 1, 3, 5]
+    );
+    assert_eq!(arena.shortest_path(Index::new(4), Index::new(5)).unwrap(), None);
+}
+
+#[test]
+fn cycles_are_detected() {
+    let mut arena = graph(&[&[1, 2], &[3], &[3, 4], &[5], &[], &[2]]);
+    assert!(matches!(arena.topological_sort(), Err(ArenaError::GraphCycle)));
+
+    let cycle = arena.find_cycle().unwrap().unwrap();
+    let mut sorted = indices(cycle.clone());
+    sorted.sort();
+    assert_eq!(sorted, [2, 3, 5]);
+    // Each element of the cycle references the next one, and the last the first.
+    cycle.iter().zip(cycle.iter().cycle().skip(1)).for_each(|(index, next)| {
+        assert!(arena.successors(*index).unwrap().contains(next));
+    });
+
+    // Removing an element of the cycle breaks it, removed elements are no longer nodes.
+    arena.remove(Index::new(3)).unwrap();
+    assert!(!arena.has_cycle().unwrap());
+    assert_eq!(indices(arena.topological_sort().unwrap()).len(), 5);
+    assert!(matches!(arena.bfs(Index::new(3)), Err(ArenaError::RemovedElementAccess)));
+}
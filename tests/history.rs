@@ -0,0 +1,147 @@
+use arena_system::{Arena, ChunkedStorage, Handle};
+use arena_system_proc_macro::Handleable;
+
+#[derive(Handleable, Debug, Clone, PartialEq)]
+#[handleable(history)]
+struct Shape {
+    #[handle_getter(return_type(copy))]
+    x: i32,
+    name: String,
+}
+
+#[derive(Handleable, Debug, Clone, PartialEq)]
+#[handleable(history)]
+struct User {
+    #[handle_index(unique)]
+    name: String,
+    #[handle_index]
+    team: u32,
+}
+
+fn shape(x: i32, name: &str) -> Shape {
+    Shape { x, name: name.into() }
+}
+
+#[test]
+fn undo_redo_add_and_remove() {
+    let mut arena = Arena::new();
+    arena.enable_history(8);
+
+    let a = arena.add(shape(1, "a"));
+    arena.remove(a).unwrap();
+    let b = arena.add(shape(2, "b"));
+    assert_eq!(a, b);
+
+    assert!(arena.undo().unwrap());
+    assert!(!arena.contains(b));
+    assert!(arena.undo().unwrap());
+    assert_eq!(*arena.lookup(a).unwrap(), shape(1, "a"));
+    assert!(arena.undo().unwrap());
+    assert!(arena.is_empty());
+    assert!(!arena.undo().unwrap());
+
+    assert!(arena.redo().unwrap());
+    assert!(arena.redo().unwrap());
+    assert!(arena.redo().unwrap());
+    assert_eq!(*arena.lookup(b).unwrap(), shape(2, "b"));
+    assert!(!arena.redo().unwrap());
+}
+
+#[test]
+fn undo_redo_transactions() {
+    let mut arena = Arena::new();
+    arena.enable_history(8);
+    let a = arena.add(shape(1, "a"));
+
+    {
+        let handle = arena.handle(a, None);
+        assert!(handle.set_x(2));
+        handle.arena().begin_transaction("rename");
+        assert!(handle.set_name("b".into()));
+        assert!(handle.set_x(3));
+        handle.arena().end_transaction();
+    }
+
+    assert_eq!(arena.undo_name(), Some("rename".to_string()));
+    assert!(arena.undo().unwrap());
+    assert_eq!(*arena.lookup(a).unwrap(), shape(2, "a"));
+    assert!(arena.redo().unwrap());
+    assert_eq!(*arena.lookup(a).unwrap(), shape(3, "b"));
+
+    let removed = arena.transaction("delete", |arena| arena.remove(a).unwrap());
+    assert_eq!(removed, shape(3, "b"));
+    assert!(arena.undo().unwrap());
+    assert_eq!(*arena.lookup(a).unwrap(), shape(3, "b"));
+
+    // A new change drops the redo stack.
+    assert!(arena.undo().unwrap());
+    arena.add(shape(4, "c"));
+    assert!(!arena.can_redo());
+}
+
+#[test]
+fn history_is_capped() {
+    let mut arena = Arena::new();
+    arena.enable_history(2);
+    let a = arena.add(shape(1, "a"));
+
+    {
+        let handle = arena.handle(a, None);
+        assert!(handle.set_x(2));
+        assert!(handle.set_x(3));
+    }
+
+    assert!(arena.undo().unwrap());
+    assert!(arena.undo().unwrap());
+    assert!(!arena.undo().unwrap());
+    assert_eq!(*arena.lookup(a).unwrap(), shape(1, "a"));
+}
+
+#[test]
+fn undo_redo_indexed_fields() {
+    let mut arena = Arena::new();
+    arena.enable_indexes().unwrap();
+    arena.enable_history(8);
+    let a = arena.add(User { name: "a".into(), team: 1 });
+
+    {
+        let handle = arena.handle(a, None);
+        assert!(handle.set_name("b".into()));
+        assert!(handle.set_team(2));
+    }
+
+    assert!(arena.undo().unwrap());
+    assert_eq!(arena.find_all_by_team(&1), [a]);
+    assert!(arena.find_all_by_team(&2).is_empty());
+    assert!(arena.undo().unwrap());
+    assert_eq!(arena.find_by_name(&"a".into()), Some(a));
+    assert_eq!(arena.find_by_name(&"b".into()), None);
+
+    assert!(arena.redo().unwrap());
+    assert!(arena.redo().unwrap());
+    assert_eq!(*arena.lookup(a).unwrap(), User { name: "b".into(), team: 2 });
+    assert_eq!(arena.find_by_name(&"b".into()), Some(a));
+    assert_eq!(arena.find_all_by_team(&2), [a]);
+}
+
+#[test]
+fn undo_redo_chunked_push() {
+    let mut arena: Arena<u32, ChunkedStorage<u32>> = Arena::chunked(4);
+    arena.enable_history(8);
+
+    let a = arena.add(1);
+    arena.add(2);
+    arena.remove(a).unwrap();
+    let pushed = arena.push(3).unwrap();
+    assert_ne!(pushed, a);
+
+    assert!(arena.undo().unwrap());
+    assert!(!arena.contains(pushed));
+    assert!(arena.undo().unwrap());
+    assert_eq!(*arena.lookup(a).unwrap(), 1);
+
+    assert!(arena.redo().unwrap());
+    assert!(arena.redo().unwrap());
+    assert!(!arena.contains(a));
+    assert_eq!(*arena.lookup(pushed).unwrap(), 3);
+}
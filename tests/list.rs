@@ -0,0 +1,77 @@
+use arena_system::{Arena, ArenaError, ArenaList, Index};
+use arena_system_proc_macro::Handleable;
+
+#[derive(Handleable, Debug)]
+struct Node {
+    #[handle_getter(return_type(copy))]
+    value: u32,
+}
+
+fn index(index: i64) -> Index {
+    Index::new(index)
+}
+
+fn items(list: &ArenaList<Node>, arena: &Arena<Node>) -> Vec<i64> {
+    list.iter(arena).map(Into::into).collect()
+}
+
+fn list(arena: &Arena<Node>) -> ArenaList<Node> {
+    let mut list = ArenaList::new();
+    (0..4).for_each(|i| list.push_back(arena, index(i)).unwrap());
+    // Linking an element which is already in the list moves it.
+    list.push_front(arena, index(3)).unwrap();
+    list.insert_after(arena, index(1), index(5)).unwrap();
+    list.insert_before(arena, index(0), index(4)).unwrap();
+
+    list
+}
+
+#[test]
+fn link_and_unlink() {
+    let arena = (0..6).map(|value| Node { value }).collect::<Arena<_>>();
+    let mut list = list(&arena);
+    assert_eq!(items(&list, &arena), [3, 4, 0, 1, 5, 2]);
+    assert_eq!(list.len(), 6);
+    assert_eq!(
+        list.handle_iter(&arena, None)
+            .map(|handle| handle.value())
+            .collect::<Vec<_>>(),
+        [Some(3), Some(4), Some(0), Some(1), Some(5), Some(2)]
+    );
+
+    assert!(list.unlink(index(0)));
+    assert!(!list.unlink(index(0)));
+    assert!(!list.contains(index(0)));
+    assert!(matches!(
+        list.insert_before(&arena, index(0), index(1)),
+        Err(ArenaError::UnlinkedElement)
+    ));
+    assert_eq!(list.pop_front(&arena), Some(index(3)));
+    assert_eq!(list.pop_back(&arena), Some(index(2)));
+    assert_eq!(items(&list, &arena), [4, 1, 5]);
+
+    list.clear();
+    assert!(list.is_empty());
+    assert_eq!(list.front(&arena), None);
+}
+
+#[test]
+fn removed_elements_are_skipped() {
+    let mut arena = (0..6).map(|value| Node { value }).collect::<Arena<_>>();
+    let mut list = list(&arena);
+    arena.remove(index(3)).unwrap();
+    arena.remove(index(2)).unwrap();
+    assert!(list.push_back(&arena, index(2)).is_err());
+
+    // The reused slot isn't linked, the list still refers to the removed element.
+    assert_eq!(arena.add(Node { value: 42 }), index(2));
+    assert_eq!(items(&list, &arena), [4, 0, 1, 5]);
+    assert_eq!(list.front(&arena), Some(index(4)));
+    assert_eq!(list.back(&arena), Some(index(5)));
+    assert_eq!(list.len(), 6);
+
+    assert_eq!(list.prune(&arena), 2);
+    assert_eq!(list.len(), 4);
+    list.push_back(&arena, index(2)).unwrap();
+    assert_eq!(items(&list, &arena), [4, 0, 1, 5, 2]);
+}
@@ -0,0 +1,102 @@
+use arena_system::{Arena, ArenaError, ArenaOp, ArenaResult, ChunkedStorage, Storage};
+use arena_system::{SnapshotReader, SnapshotValue, SnapshotWriter};
+use arena_system_proc_macro::Handleable;
+
+use std::sync::mpsc;
+
+#[derive(Handleable, Debug, Clone, PartialEq)]
+#[handleable(oplog, history)]
+struct Unit {
+    #[handle_getter(return_type(copy))]
+    hp: i32,
+    name: String,
+}
+
+impl SnapshotValue for Unit {
+    fn encode(&self, writer: &mut SnapshotWriter) {
+        writer.write(&self.hp);
+        writer.write(&self.name);
+    }
+
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+        Ok(Self { hp: reader.read()?, name: reader.read()? })
+    }
+}
+
+fn unit(hp: i32, name: &str) -> Unit {
+    Unit { hp, name: name.into() }
+}
+
+fn replicate(ops: impl IntoIterator<Item = ArenaOp>) -> Arena<Unit> {
+    let mut replica = Arena::new();
+    for op in ops {
+        // Ops go through their wire encoding, like they would over the network.
+        let mut writer = SnapshotWriter::new();
+        writer.write(&op);
+        let bytes = writer.into_bytes();
+        let decoded: ArenaOp = SnapshotReader::new(&bytes).read().unwrap();
+        assert_eq!(decoded, op);
+
+        replica.apply(&decoded).unwrap();
+    }
+
+    replica
+}
+
+fn assert_identical<S: Storage<Unit>>(source: &Arena<Unit, S>, replica: &Arena<Unit>) {
+    let (source, replica) = (source.storage(), replica.storage());
+    assert_eq!(replica.capacity(), source.capacity());
+    assert_eq!(replica.occupied().collect::<Vec<_>>(), source.occupied().collect::<Vec<_>>());
+
+    for slot in 0..source.capacity() {
+        assert_eq!(replica.generation(slot), source.generation(slot));
+        if source.is_occupied(slot) {
+            assert_eq!(*replica.lookup(slot).unwrap(), *source.lookup(slot).unwrap());
+        }
+    }
+}
+
+#[test]
+fn replica_matches_source() {
+    let (sender, receiver) = mpsc::channel();
+    let mut source = Arena::new();
+    source.enable_history(16);
+    source.enable_op_log(move |op| sender.send(op).unwrap());
+
+    let a = source.add(unit(10, "a"));
+    let b = source.add(unit(5, "b"));
+    source.remove(a).unwrap();
+    source.add(unit(7, "c"));
+    {
+        let handle = source.handle(b, None);
+        assert!(handle.set_hp(1));
+        assert!(handle.set_name("bb".into()));
+    }
+    source.undo().unwrap();
+    source.transaction("grow", |source| {
+        source.add(unit(1, "d"));
+        source.add(unit(2, "e"));
+    });
+    source.undo().unwrap();
+
+    let ops = receiver.try_iter().collect::<Vec<_>>();
+    let mut replica = replicate(ops.iter().cloned());
+    assert_identical(&source, &replica);
+
+    assert!(matches!(replica.apply(&ops[0]), Err(ArenaError::OpLogConflict)));
+}
+
+#[test]
+fn replica_matches_chunked_push() {
+    let (sender, receiver) = mpsc::channel();
+    let mut source: Arena<Unit, ChunkedStorage<Unit>> = Arena::chunked(2);
+    source.enable_op_log(move |op| sender.send(op).unwrap());
+
+    let a = source.add(unit(1, "a"));
+    source.add(unit(2, "b"));
+    source.remove(a).unwrap();
+    source.push(unit(3, "pushed")).unwrap();
+    source.add(unit(4, "c"));
+
+    assert_identical(&source, &replicate(receiver.try_iter()));
+}
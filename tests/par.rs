@@ -0,0 +1,43 @@
+#![cfg(feature = "rayon")]
+
+use arena_system::{Arena, ArenaError, Index};
+
+use rayon::prelude::*;
+
+#[test]
+fn par_iter_blocks_lookup_mut() {
+    let mut arena: Arena<u64> = (0..100).collect();
+    for i in (0..100).step_by(3) {
+        arena.remove(Index::new(i)).unwrap();
+    }
+
+    let elements = arena.par_iter();
+    assert_eq!(elements.len(), 66);
+    assert!(matches!(arena.lookup_mut(Index::new(1)), Err(ArenaError::BorrowError(_))));
+    assert!(arena.lookup(Index::new(1)).is_ok());
+    assert_eq!((&elements).into_par_iter().filter(|element| **element % 2 == 0).count(), 33);
+    drop(elements);
+
+    assert!(arena.lookup_mut(Index::new(1)).is_ok());
+}
+
+#[test]
+fn par_iter_skips_mutably_borrowed() {
+    let arena: Arena<u64> = (0..10).collect();
+
+    let mut element = arena.lookup_mut(Index::new(4)).unwrap();
+    let elements = arena.par_iter();
+    assert_eq!(elements.len(), 9);
+    assert_eq!(elements.iter().sum::<u64>(), 45 - 4);
+    *element = 0;
+}
+
+#[test]
+fn par_iter_mut_visits_live_elements() {
+    let mut arena: Arena<u64> = (0..10).collect();
+    arena.remove(Index::new(0)).unwrap();
+
+    arena.par_iter_mut().for_each(|element| *element *= 2);
+    assert_eq!(arena.par_iter().iter().sum::<u64>(), 90);
+    assert!(!arena.contains(Index::new(0)));
+}
@@ -0,0 +1,109 @@
+use arena_system::{impl_pod_struct, Arena, ArenaError, Index, PodArena};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Vertex {
+    position: [f32; 3],
+    _pad: u32,
+    next: Index,
+}
+
+impl_pod_struct!(Vertex { position: [f32; 3], _pad: u32, next: Index });
+
+// Offsets of the header fields.
+const CAPACITY: usize = 24;
+const LEN: usize = 32;
+
+fn pod_bytes() -> (Vec<u8>, Vec<Index>) {
+    let mut arena = Arena::new();
+    let indices = (0..5)
+        .map(|i| arena.add(Vertex { position: [i as f32; 3], _pad: 0, next: Index::new(i + 1) }))
+        .collect::<Vec<_>>();
+    arena.remove(indices[2]).unwrap();
+
+    let mut bytes = vec![];
+    arena.write_pod(&mut bytes).unwrap();
+
+    (bytes, indices)
+}
+
+fn read(bytes: &[u8]) -> Result<PodArena<Vertex>, ArenaError> {
+    PodArena::read_from(&mut &bytes[..])
+}
+
+#[test]
+fn round_trip() {
+    let (bytes, indices) = pod_bytes();
+    let arena = read(&bytes).unwrap();
+
+    assert_eq!(arena.len(), 4);
+    assert_eq!(arena.capacity(), 5);
+    assert_eq!(arena.get(indices[3]).unwrap().position, [3.0; 3]);
+    assert!(matches!(arena.get(indices[2]), Err(ArenaError::RemovedElementAccess)));
+    assert_eq!(arena.generation(indices[2]), Some(1));
+    assert_eq!(
+        arena
+            .to_arena()
+            .add(Vertex { position: [0.0; 3], _pad: 0, next: Index::invalid() }),
+        indices[2]
+    );
+}
+
+#[test]
+fn open_file() {
+    let (bytes, indices) = pod_bytes();
+    let path = std::env::temp_dir().join(format!("arena_system_pod_{}.bin", std::process::id()));
+    std::fs::write(&path, bytes).unwrap();
+
+    // SAFETY: the file isn't modified while it's open.
+    let opened = unsafe { PodArena::<Vertex>::open(&path) }.unwrap();
+    assert_eq!(opened.indices().collect::<Vec<_>>(), [0, 1, 3, 4].map(|i| indices[i]));
+    drop(opened);
+
+    let mismatch = unsafe { PodArena::<u64>::open(&path) };
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(mismatch, Err(ArenaError::PodLayoutMismatch("element size", 8, _))));
+}
+
+#[test]
+fn bad_magic() {
+    let (mut bytes, _) = pod_bytes();
+    bytes[0] ^= 0xff;
+
+    assert!(matches!(read(&bytes), Err(ArenaError::PodLayoutMismatch("magic", _, _))));
+}
+
+#[test]
+fn overflowing_capacity() {
+    let (mut bytes, _) = pod_bytes();
+    bytes[CAPACITY..CAPACITY + 8].copy_from_slice(&u64::MAX.to_ne_bytes());
+
+    assert!(matches!(read(&bytes), Err(ArenaError::PodCapacityOverflow(u64::MAX))));
+}
+
+#[test]
+fn capacity_beyond_file() {
+    let (mut bytes, _) = pod_bytes();
+    bytes[CAPACITY..CAPACITY + 8].copy_from_slice(&(1u64 << 40).to_ne_bytes());
+
+    assert!(matches!(read(&bytes), Err(ArenaError::PodLayoutMismatch("file size", _, _))));
+}
+
+#[test]
+fn truncated() {
+    let (bytes, _) = pod_bytes();
+
+    assert!(matches!(
+        read(&bytes[..bytes.len() - 1]),
+        Err(ArenaError::PodLayoutMismatch("file size", _, _))
+    ));
+    assert!(read(&bytes[..8]).is_err());
+}
+
+#[test]
+fn wrong_len() {
+    let (mut bytes, _) = pod_bytes();
+    bytes[LEN..LEN + 8].copy_from_slice(&5u64.to_ne_bytes());
+
+    assert!(matches!(read(&bytes), Err(ArenaError::PodLayoutMismatch("len", 5, 4))));
+}
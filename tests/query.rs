@@ -0,0 +1,68 @@
+use arena_system::{Arena, Index, Storage};
+use arena_system_proc_macro::Handleable;
+
+#[derive(Handleable, Debug)]
+struct Item {
+    #[handle_getter(return_type(copy))]
+    value: u32,
+}
+
+fn sorted(indices: impl IntoIterator<Item = Index>) -> Vec<i64> {
+    let mut indices = indices.into_iter().map(Into::into).collect::<Vec<_>>();
+    indices.sort();
+
+    indices
+}
+
+fn assert_filters<S: Storage<Item>>(mut arena: Arena<Item, S>) {
+    (0..10).for_each(|value| {
+        arena.add(Item { value });
+    });
+    arena.remove(Index::new(4)).unwrap();
+    arena.remove(Index::new(1)).unwrap();
+
+    let min = 3;
+    let even = arena
+        .query()
+        .filter(|item| item.value % 2 == 0)
+        .filter(move |item| item.value > min);
+    assert_eq!(sorted(even), [6, 8]);
+    assert_eq!(arena.query().filter(|item| item.value < 3).count(), 2);
+    assert!(!arena.query().any(|item| item.value == 4));
+    assert_eq!(arena.query().find(|item| item.value == 7), Some(Index::new(7)));
+
+    let mut values = arena
+        .query()
+        .filter(|item| item.value > 6)
+        .map_handles(None)
+        .map(|handle| handle.value())
+        .collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(values, [Some(7), Some(8), Some(9)]);
+
+    // Mutably borrowed elements are skipped.
+    let element = arena.lookup_mut(Index::new(7)).unwrap();
+    assert_eq!(arena.query().count(), 7);
+    drop(element);
+    assert_eq!(arena.query().count(), 8);
+}
+
+#[test]
+fn filters_skip_removed_elements() {
+    assert_filters(Arena::new());
+    assert_filters(Arena::chunked(4));
+    assert_filters(Arena::sparse_set());
+}
+
+#[test]
+fn sparse_queries_skip_reused_slots_once_removed() {
+    let mut arena = Arena::sparse_set();
+    let [a, b, c] = [1, 2, 3].map(|value| arena.add(Item { value }));
+    arena.remove(a).unwrap();
+    assert_eq!(sorted(arena.query()), sorted([b, c]));
+
+    let d = arena.add(Item { value: 4 });
+    assert_eq!(d, a);
+    arena.remove(b).unwrap();
+    assert_eq!(sorted(arena.query().filter(|item| item.value > 1)), sorted([c, d]));
+}
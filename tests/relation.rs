@@ -0,0 +1,98 @@
+use arena_system::{arena_world, Relation, World};
+use arena_system_proc_macro::Handleable;
+
+arena_world! {
+    pub struct Scene {
+        nodes: Node,
+        tags: Tag,
+    }
+    relations {
+        pub hierarchy: Relation<Node, Node> = Relation::one_to_many(),
+        pub tagging: Relation<Node, Tag>,
+    }
+}
+
+#[derive(Handleable)]
+#[handleable(world = Scene)]
+#[handle_relations(
+    children(vis(pub), relation(hierarchy), targets(Node)),
+    parent(vis(pub), relation(hierarchy), source(Node)),
+    tags(vis(pub), relation(tagging), targets(Tag))
+)]
+pub struct Node {
+    #[handle_getter(return_type(copy))]
+    id: u32,
+}
+
+#[derive(Handleable)]
+#[handleable(world = Scene)]
+#[handle_relations(tagged(vis(pub), relation(tagging), sources(Node)))]
+pub struct Tag {
+    #[handle_getter(return_type(clone))]
+    name: String,
+}
+
+fn node_ids(nodes: Vec<NodeHandle<'_>>) -> Vec<u32> {
+    nodes.iter().map(|node| node.id().unwrap()).collect()
+}
+
+#[test]
+fn links_resolve_both_ways() {
+    let mut scene = Scene::new();
+    let (root, a, b) =
+        (scene.add(Node { id: 0 }), scene.add(Node { id: 1 }), scene.add(Node { id: 2 }));
+    let tag = scene.add(Tag { name: "tag".into() });
+    let node = |scene: &Scene, index| scene.nodes().detach(index).unwrap();
+    let (root, a, b) = (node(&scene, root), node(&scene, a), node(&scene, b));
+    let tag = scene.tags().detach(tag).unwrap();
+
+    scene.hierarchy.link(root, a);
+    scene.hierarchy.link(root, b);
+    // One-to-many relations move the target to its new source.
+    scene.hierarchy.link(a, b);
+    scene.tagging.link(a, tag);
+    scene.tagging.link(b, tag);
+
+    assert_eq!(node_ids(scene.handle::<Node>(root.index()).children()), [1]);
+    assert_eq!(scene.handle::<Node>(b.index()).parent().unwrap().id(), Some(1));
+    assert_eq!(scene.handle::<Tag>(tag.index()).tagged().len(), 2);
+    assert_eq!(scene.handle::<Node>(b.index()).tags()[0].name().unwrap(), "tag");
+
+    assert!(scene.hierarchy.unlink(a, b));
+    assert!(scene.handle::<Node>(b.index()).parent().is_none());
+}
+
+#[test]
+fn world_remove_drops_links() {
+    let mut scene = Scene::new();
+    let (root, child) = (scene.add(Node { id: 0 }), scene.add(Node { id: 1 }));
+    let tag = scene.add(Tag { name: "tag".into() });
+    let (root_handle, child_handle) =
+        (scene.nodes().detach(root).unwrap(), scene.nodes().detach(child).unwrap());
+    scene.hierarchy.link(root_handle, child_handle);
+    scene.tagging.link(child_handle, scene.tags().detach(tag).unwrap());
+
+    scene.remove::<Node>(child).unwrap();
+    assert!(scene.hierarchy.targets(root_handle).is_empty());
+    assert!(scene.handle::<Tag>(tag).tagged().is_empty());
+}
+
+#[test]
+fn arena_remove_leaves_links_until_pruned() {
+    let mut scene = Scene::new();
+    let (root, child) = (scene.add(Node { id: 0 }), scene.add(Node { id: 1 }));
+    let (root_handle, child_handle) =
+        (scene.nodes().detach(root).unwrap(), scene.nodes().detach(child).unwrap());
+    scene.hierarchy.link(root_handle, child_handle);
+
+    scene.arena_mut::<Node>().remove(child).unwrap();
+    let reused = scene.add(Node { id: 2 });
+    assert_eq!(reused, child);
+    assert!(scene.handle::<Node>(root).children().is_empty());
+    assert!(scene.handle::<Node>(reused).parent().is_none());
+    assert_eq!(scene.hierarchy.targets(root_handle), [child_handle]);
+
+    let Scene { nodes, hierarchy, .. } = &mut scene;
+    hierarchy.prune(nodes, nodes);
+    assert!(scene.hierarchy.targets(root_handle).is_empty());
+}
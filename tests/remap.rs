@@ -0,0 +1,81 @@
+use arena_system::{Arena, ArenaError, Index};
+use arena_system_proc_macro::{Handleable, References};
+
+#[derive(Handleable, References, Debug, Clone, PartialEq)]
+struct Node {
+    #[handle_index(unique)]
+    name: String,
+    #[reference]
+    parent: Index,
+}
+
+fn node(name: &str, parent: Index) -> Node {
+    Node { name: name.into(), parent }
+}
+
+#[test]
+fn merge_remaps_references() {
+    let mut target = Arena::new();
+    target.add(node("a", Index::invalid()));
+
+    let mut other = Arena::new();
+    let root = other.add(node("b", Index::invalid()));
+    let removed = other.add(node("removed", Index::invalid()));
+    other.remove(removed).unwrap();
+    let child = other.add(node("c", root));
+    other.lookup_mut(root).unwrap().parent = Index::new(7);
+
+    let remap = target.merge_remapped(other).unwrap();
+    assert_eq!(remap.len(), 2);
+    let (root, child) = (remap.get(root).unwrap(), remap.get(child).unwrap());
+    assert_eq!(target.lookup(child).unwrap().parent, root);
+    // The reference was dangling in `other`, so it doesn't point at an element of the target.
+    assert!(target.lookup(root).unwrap().parent.is_invalid());
+}
+
+#[test]
+fn merge_rejects_duplicate_key() {
+    let mut target = Arena::new();
+    target.add(node("a", Index::invalid()));
+    target.enable_indexes().unwrap();
+
+    let other: Arena<Node> = vec![node("b", Index::invalid()), node("a", Index::invalid())].into();
+    assert!(matches!(target.merge(other), Err(ArenaError::DuplicateIndexKey("name"))));
+    assert_eq!(target.len(), 1);
+    assert_eq!(target.find_by_name(&"b".into()), None);
+
+    let other: Arena<Node> = vec![node("b", Index::invalid()), node("b", Index::invalid())].into();
+    assert!(matches!(target.merge(other), Err(ArenaError::DuplicateIndexKey("name"))));
+    assert_eq!(target.len(), 1);
+}
+
+#[test]
+fn clone_subgraph_remaps_references() {
+    let mut arena: Arena<Node> = Arena::new();
+    let outside = arena.add(node("outside", Index::invalid()));
+    let root = arena.add(node("root", outside));
+    let child = arena.add(node("child", root));
+    let dangling = arena.add(node("dangling", Index::invalid()));
+    arena.lookup_mut(child).unwrap().parent = dangling;
+    arena.remove(dangling).unwrap();
+
+    let remap = arena.clone_subgraph_by([root, child], |_| false).unwrap();
+    assert_eq!(arena.lookup(remap.get(root).unwrap()).unwrap().parent, outside);
+    assert!(arena.lookup(remap.get(child).unwrap()).unwrap().parent.is_invalid());
+    assert_eq!(arena.lookup(child).unwrap().parent, dangling);
+
+    arena.lookup_mut(child).unwrap().parent = root;
+    let remap = arena.clone_subgraph_by([root, child], |_| false).unwrap();
+    assert_eq!(arena.lookup(remap.get(child).unwrap()).unwrap().parent, remap.get(root).unwrap());
+}
+
+#[test]
+fn clone_subgraph_rejects_duplicate_key() {
+    let mut arena = Arena::new();
+    let root = arena.add(node("root", Index::invalid()));
+    arena.add(node("child", root));
+    arena.enable_indexes().unwrap();
+
+    assert!(matches!(arena.clone_subgraph([root]), Err(ArenaError::DuplicateIndexKey("name"))));
+    assert_eq!(arena.len(), 2);
+}
@@ -0,0 +1,66 @@
+use arena_system::{ArcArena, Arena, RcArena};
+use arena_system_proc_macro::Handleable;
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(Handleable, Debug)]
+#[handleable(shared)]
+struct Widget<T> {
+    #[handle_getter(return_type(copy))]
+    clicks: u32,
+    // Only held to count when elements are dropped.
+    #[allow(dead_code)]
+    payload: T,
+}
+
+#[test]
+fn rc_handles_keep_the_arena_alive() {
+    let payload = Rc::new(());
+    let widgets = RcArena::new();
+    let index = widgets.add(Widget { clicks: 0, payload: payload.clone() });
+    let handle = widgets.handle(index);
+    let callback = move || handle.set_clicks(handle.clicks().unwrap() + 1);
+
+    drop(widgets);
+    assert!(callback());
+    assert!(callback());
+    assert_eq!(Rc::strong_count(&payload), 2);
+
+    // The elements are dropped together with the last handle.
+    drop(callback);
+    assert_eq!(Rc::strong_count(&payload), 1);
+}
+
+#[test]
+fn rc_handles_fail_while_the_arena_is_borrowed() {
+    let widgets = RcArena::new();
+    let index = widgets.add(Widget { clicks: 3, payload: () });
+    let handle = widgets.handle(index);
+
+    let arena = widgets.borrow();
+    assert!(widgets.try_add(Widget { clicks: 0, payload: () }).is_err());
+    assert_eq!(handle.clicks(), Some(3));
+    drop(arena);
+
+    assert!(handle.set_clicks(4));
+    assert_eq!(widgets.borrow().lookup(index).unwrap().clicks, 4);
+}
+
+#[test]
+fn arc_handles_outlive_the_arena_across_threads() {
+    let payload = Arc::new(());
+    let widgets = ArcArena::from(Arena::from(vec![Widget { clicks: 5, payload: payload.clone() }]));
+    let handle = widgets.handle(0usize.into());
+    drop(widgets);
+
+    let clicks = std::thread::spawn(move || {
+        handle.set_clicks(handle.clicks().unwrap() * 2);
+
+        handle.clicks()
+    })
+    .join()
+    .unwrap();
+    assert_eq!(clicks, Some(10));
+    assert_eq!(Arc::strong_count(&payload), 1);
+}
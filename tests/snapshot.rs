@@ -0,0 +1,170 @@
+use arena_system::{Arena, ArenaError, ArenaResult, Index, Snapshot, SnapshotElement};
+use arena_system::{SnapshotMigrations, SnapshotReader, SnapshotValue, SnapshotWriter};
+
+mod v1 {
+    use arena_system::SnapshotWriter;
+    use arena_system::{ArenaResult, SnapshotElement, SnapshotReader, SnapshotValue};
+
+    pub struct Mesh {
+        pub vertices: u32,
+    }
+
+    impl SnapshotValue for Mesh {
+        fn encode(&self, writer: &mut SnapshotWriter) {
+            writer.write(&self.vertices);
+        }
+
+        fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+            Ok(Self { vertices: reader.read()? })
+        }
+    }
+
+    impl SnapshotElement for Mesh {
+        const SNAPSHOT_NAME: &'static str = "mesh";
+        const SCHEMA_VERSION: u32 = 1;
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Mesh {
+    vertices: u32,
+    name: String,
+}
+
+impl SnapshotValue for Mesh {
+    fn encode(&self, writer: &mut SnapshotWriter) {
+        writer.write(&self.vertices);
+        writer.write(&self.name);
+    }
+
+    fn decode(reader: &mut SnapshotReader<'_>) -> ArenaResult<Self> {
+        Ok(Self { vertices: reader.read()?, name: reader.read()? })
+    }
+}
+
+impl SnapshotElement for Mesh {
+    const SNAPSHOT_NAME: &'static str = "mesh";
+    const SCHEMA_VERSION: u32 = 2;
+}
+
+fn v1_snapshot() -> (Vec<u8>, Vec<Index>) {
+    let mut arena = Arena::new();
+    let indices = (0..3).map(|i| arena.add(v1::Mesh { vertices: i })).collect::<Vec<_>>();
+    arena.remove(indices[0]).unwrap();
+
+    let mut snapshot = Snapshot::new();
+    snapshot.add_arena(&arena).unwrap();
+
+    (snapshot.to_bytes(), indices)
+}
+
+#[test]
+fn round_trip_keeps_slots() {
+    let mut arena = Arena::new();
+    let a = arena.add(Mesh { vertices: 1, name: "a".into() });
+    let b = arena.add(Mesh { vertices: 2, name: "b".into() });
+    arena.remove(a).unwrap();
+
+    let mut snapshot = Snapshot::new();
+    snapshot.add_arena(&arena).unwrap();
+    let mut loaded = Snapshot::from_bytes(&snapshot.to_bytes())
+        .unwrap()
+        .load_arena::<Mesh>()
+        .unwrap();
+
+    assert_eq!(*loaded.lookup(b).unwrap(), Mesh { vertices: 2, name: "b".into() });
+    assert!(!loaded.contains(a));
+    assert_eq!(loaded.add(Mesh { vertices: 3, name: "c".into() }), a);
+}
+
+#[test]
+fn migration_round_trip() {
+    let (bytes, indices) = v1_snapshot();
+    let snapshot = Snapshot::from_bytes(&bytes).unwrap();
+    assert_eq!(snapshot.schema_version::<Mesh>(), Some(1));
+    assert!(matches!(
+        snapshot.load_arena::<Mesh>(),
+        Err(ArenaError::UnsupportedSnapshotVersion("mesh", 1))
+    ));
+
+    let mut migrations = SnapshotMigrations::new();
+    migrations.register(1, |reader| Ok(Mesh { vertices: reader.read()?, name: "unnamed".into() }));
+    let migrated = snapshot.load_arena_with(&migrations).unwrap();
+    assert_eq!(migrated.len(), 2);
+    assert!(!migrated.contains(indices[0]));
+    assert_eq!(*migrated.lookup(indices[2]).unwrap(), Mesh { vertices: 2, name: "unnamed".into() });
+
+    // Saving the migrated arena writes the current schema version.
+    let mut snapshot = Snapshot::new();
+    snapshot.add_arena(&migrated).unwrap();
+    let snapshot = Snapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+    assert_eq!(snapshot.schema_version::<Mesh>(), Some(2));
+    let reloaded = snapshot.load_arena::<Mesh>().unwrap();
+    assert_eq!(*reloaded.lookup(indices[1]).unwrap(), Mesh { vertices: 1, name: "unnamed".into() });
+}
+
+#[test]
+fn round_trip_keeps_reuse_order() {
+    let mut arena: Arena<Mesh> = (0..4).map(|i| Mesh { vertices: i, name: "".into() }).collect();
+    [Index::new(1), Index::new(3), Index::new(0)]
+        .into_iter()
+        .for_each(|index| {
+            arena.remove(index).unwrap();
+        });
+
+    let mut snapshot = Snapshot::new();
+    snapshot.add_arena(&arena).unwrap();
+    let mut loaded = Snapshot::from_bytes(&snapshot.to_bytes())
+        .unwrap()
+        .load_arena::<Mesh>()
+        .unwrap();
+
+    let mesh = || Mesh { vertices: 9, name: "new".into() };
+    let added = (0..4).map(|_| arena.add(mesh())).collect::<Vec<_>>();
+    let loaded_added = (0..4).map(|_| loaded.add(mesh())).collect::<Vec<_>>();
+    assert_eq!(loaded_added, added);
+}
+
+#[test]
+fn trailing_element_data_is_rejected() {
+    let (bytes, _) = v1_snapshot();
+
+    // A v2 element decoded with the v1 schema leaves its name unread.
+    let mut arena = Arena::new();
+    arena.add(Mesh { vertices: 1, name: "name".into() });
+    let mut snapshot = Snapshot::new();
+    snapshot.add_arena(&arena).unwrap();
+    let snapshot = Snapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+    assert!(matches!(
+        snapshot.load_arena::<v1::Mesh>(),
+        Err(ArenaError::UnsupportedSnapshotVersion("mesh", 2))
+    ));
+
+    let mut migrations = SnapshotMigrations::<v1::Mesh>::new();
+    migrations.register(2, |reader| Ok(v1::Mesh { vertices: reader.read()? }));
+    assert!(matches!(
+        snapshot.load_arena_with(&migrations),
+        Err(ArenaError::SnapshotCorrupted("trailing element data"))
+    ));
+
+    assert!(Snapshot::from_bytes(&bytes).unwrap().load_arena::<v1::Mesh>().is_ok());
+}
+
+#[test]
+fn truncated_snapshot_is_rejected() {
+    let (bytes, _) = v1_snapshot();
+
+    assert!(Snapshot::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn varint_overflow_is_rejected() {
+    let mut writer = SnapshotWriter::new();
+    writer.write(&u64::MAX);
+    let bytes = writer.into_bytes();
+    assert_eq!(SnapshotReader::new(&bytes).read::<u64>().unwrap(), u64::MAX);
+
+    let overflow = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+    assert!(SnapshotReader::new(&overflow).read_varint().is_err());
+    assert!(SnapshotReader::new(&[0x80; 11]).read_varint().is_err());
+}
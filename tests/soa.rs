@@ -0,0 +1,57 @@
+use arena_system::Index;
+use arena_system_proc_macro::Handleable;
+
+#[derive(Handleable, Debug, Clone, PartialEq)]
+#[handleable(soa)]
+struct Particle {
+    #[handle_getter(return_type(copy))]
+    x: f32,
+    #[handle_getter(return_type(copy))]
+    #[handle_setter(name(set_velocity))]
+    velocity: f32,
+    #[handle_getter(return_type(clone), name(label))]
+    name: String,
+    tags: Vec<u8>,
+}
+
+fn particle(i: u8) -> Particle {
+    Particle { x: i.into(), velocity: 1.0, name: format!("p{i}"), tags: vec![i] }
+}
+
+#[test]
+fn elements_round_trip_through_columns() {
+    let mut arena = (0..5).map(particle).collect::<ParticleSoaArena>();
+    assert_eq!(arena.len(), 5);
+
+    assert_eq!(arena.remove(Index::new(2)).unwrap(), particle(2));
+    assert!(arena.remove(Index::new(2)).is_err());
+    assert!(!arena.handle(Index::new(2)).exists());
+    assert_eq!(arena.indices().count(), 4);
+
+    // The removed slot is reused, every column gets the new element's field.
+    let added = Particle { x: 0.5, velocity: 0.0, name: "new".into(), tags: vec![] };
+    assert_eq!(arena.add(added.clone()), Index::new(2));
+    let handle = arena.handle(Index::new(2));
+    assert_eq!(handle.x(), Some(0.5));
+    assert_eq!(handle.label().as_deref(), Some("new"));
+    assert_eq!(arena.remove(Index::new(2)).unwrap(), added);
+}
+
+#[test]
+fn columns_are_borrowed_separately() {
+    let mut arena = (0..3).map(particle).collect::<ParticleSoaArena>();
+    arena.iter_x_mut().for_each(|(_, mut x)| *x += 10.0);
+    assert_eq!(arena.iter_x().map(|(_, x)| *x).collect::<Vec<_>>(), [10.0, 11.0, 12.0]);
+
+    let handle = arena.handle(Index::new(1));
+    let tags = handle.tags().unwrap();
+    // Other columns of a borrowed element can still be written.
+    assert!(handle.set_velocity(3.0));
+    assert!(handle.set_name("q".into()));
+    assert!(!handle.set_tags(vec![]));
+    drop(tags);
+    assert!(handle.set_tags(vec![]));
+
+    let removed = arena.remove(Index::new(1)).unwrap();
+    assert_eq!(removed, Particle { x: 11.0, velocity: 3.0, name: "q".into(), tags: vec![] });
+}
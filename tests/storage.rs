@@ -0,0 +1,101 @@
+use arena_system::{Arena, ArenaError, BorrowError, Index, Storage};
+
+fn assert_borrow_errors<S: Storage<u32>>(mut arena: Arena<u32, S>) {
+    let index = arena.add(1);
+
+    let element = arena.lookup_mut(index).unwrap();
+    assert!(matches!(
+        arena.lookup(index),
+        Err(ArenaError::BorrowError(BorrowError::ElementAlreadyBorrowedMutably))
+    ));
+    drop(element);
+
+    let element = arena.lookup(index).unwrap();
+    assert!(matches!(
+        arena.lookup_mut(index),
+        Err(ArenaError::BorrowError(BorrowError::ElementAlreadyBorrowed))
+    ));
+    drop(element);
+
+    assert!(arena.lookup(Index::new(1)).is_err());
+}
+
+#[test]
+fn borrow_errors_match_across_storages() {
+    assert_borrow_errors(Arena::new());
+    assert_borrow_errors(Arena::chunked(4));
+    assert_borrow_errors(Arena::sparse_set());
+}
+
+fn assert_removal_and_reuse<S: Storage<u32>>(mut arena: Arena<u32, S>) {
+    let indices = (0..6).map(|value| arena.add(value)).collect::<Vec<_>>();
+    let removed = arena.detach(indices[1]).unwrap();
+    assert_eq!(arena.remove(indices[1]).unwrap(), 1);
+    assert_eq!(arena.remove(indices[4]).unwrap(), 4);
+    assert!(matches!(arena.remove(indices[4]), Err(ArenaError::RemovedElementAccess)));
+    assert_eq!(arena.len(), 4);
+    assert_eq!(arena.indices().collect::<Vec<_>>().len(), 4);
+    assert!(!arena.contains(indices[1]));
+
+    // The last removed slot is reused first, under a new generation.
+    assert_eq!(arena.add(7), indices[4]);
+    assert_eq!(arena.add(8), indices[1]);
+    assert_eq!(arena.add(9), Index::new(6));
+    assert_eq!(*arena.lookup(indices[1]).unwrap(), 8);
+    assert!(!removed.is_live(&arena));
+    assert_eq!(arena.len(), 7);
+    assert_eq!(arena.capacity(), 7);
+}
+
+#[test]
+fn removed_slots_are_reused() {
+    assert_removal_and_reuse(Arena::new());
+    assert_removal_and_reuse(Arena::chunked(4));
+    assert_removal_and_reuse(Arena::sparse_set());
+}
+
+#[test]
+fn chunked_elements_stay_borrowed_while_pushing() {
+    let mut arena = Arena::chunked(2);
+    let first = arena.add(1u32);
+    let removed = arena.add(2);
+    arena.remove(removed).unwrap();
+
+    let mut element = arena.lookup_mut(first).unwrap();
+    let pushed = (0..5).map(|value| arena.push(value).unwrap()).collect::<Vec<_>>();
+    *element += 10;
+    drop(element);
+
+    // Pushing never reuses the removed slot.
+    assert_eq!(pushed.first(), Some(&Index::new(2)));
+    assert!(!arena.contains(removed));
+    assert_eq!(*arena.lookup(first).unwrap(), 11);
+    assert_eq!(arena.add(3), removed);
+}
+
+fn elements<S: Storage<u32>>(arena: &Arena<u32, S>) -> Vec<(i64, u32)> {
+    arena.iter().map(|(index, value)| (index.into(), *value)).collect()
+}
+
+#[test]
+fn sparse_set_removal_moves_the_last_element_into_the_hole() {
+    let mut arena = Arena::sparse_set();
+    let indices = (0..6).map(|value| arena.add(value * 10)).collect::<Vec<_>>();
+    let moved = arena.detach(indices[5]).unwrap();
+    arena.remove(indices[1]).unwrap();
+    arena.remove(indices[4]).unwrap();
+
+    // Iteration follows the packed order, indices of moved elements don't change.
+    assert_eq!(elements(&arena), [(0, 0), (5, 50), (2, 20), (3, 30)]);
+    assert!(moved.is_live(&arena));
+    assert_eq!(*arena.lookup(indices[5]).unwrap(), 50);
+
+    assert_eq!(arena.add(60), indices[4]);
+    assert_eq!(elements(&arena), [(0, 0), (5, 50), (2, 20), (3, 30), (4, 60)]);
+
+    arena.indices().collect::<Vec<_>>().into_iter().for_each(|index| {
+        arena.remove(index).unwrap();
+    });
+    assert!(arena.is_empty());
+    assert_eq!(arena.iter().count(), 0);
+}
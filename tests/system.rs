@@ -0,0 +1,60 @@
+use arena_system::{ArenaSystem, Index};
+use arena_system_proc_macro::References;
+
+#[derive(References, Debug)]
+struct Node {
+    name: &'static str,
+    #[reference(Mesh)]
+    mesh: Index,
+    #[reference]
+    children: Vec<Index>,
+}
+
+#[derive(Debug)]
+struct Mesh {
+    vertices: u32,
+}
+
+#[test]
+fn arenas_are_created_per_element_type() {
+    let mut system = ArenaSystem::new();
+    assert!(!system.has_arena::<Mesh>());
+    assert!(system.arena::<Mesh>().is_empty());
+
+    let mesh = system.add(Mesh { vertices: 3 });
+    let node = system.add(Node { name: "node", mesh, children: vec![] });
+    assert!(system.has_arena::<Mesh>());
+    assert!(!system.has_arena::<u32>());
+
+    // Both arenas start at index 0, lookups only go to the arena of the requested type.
+    assert_eq!(mesh, node);
+    assert_eq!(system.lookup::<Mesh>(mesh).unwrap().vertices, 3);
+    assert_eq!(system.lookup::<Node>(node).unwrap().name, "node");
+    assert!(system.lookup::<u32>(node).is_err());
+
+    assert_eq!(system.remove::<Mesh>(mesh).unwrap().vertices, 3);
+    assert!(system.lookup::<Mesh>(mesh).is_err());
+    assert!(system.lookup::<Node>(node).is_ok());
+}
+
+#[test]
+fn handles_follow_references_into_other_arenas() {
+    let mut system = ArenaSystem::new();
+    let mesh = system.add(Mesh { vertices: 3 });
+    let child = system.add(Node { name: "child", mesh, children: vec![] });
+    let root = system.add(Node { name: "root", mesh, children: vec![child] });
+
+    let handle = system.system_handle::<Node>(root);
+    let mesh_handle = handle.follow::<Mesh>(|node| node.mesh).unwrap();
+    // Elements of different arenas can be borrowed at the same time.
+    let node = handle.get().unwrap();
+    mesh_handle.get_mut().unwrap().vertices += 1;
+    assert_eq!(node.name, "root");
+    drop(node);
+
+    let children = handle.references::<Node>();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].get().unwrap().name, "child");
+    assert_eq!(handle.references::<Mesh>()[0].get().unwrap().vertices, 4);
+    assert!(system.arena::<Node>().validate_against(system.arena::<Mesh>()).is_valid());
+}
@@ -0,0 +1,84 @@
+use arena_system::{ArenaError, Index, TreeArena};
+use arena_system_proc_macro::Handleable;
+
+#[derive(Handleable, Debug, PartialEq)]
+struct Node {
+    #[handle_getter(return_type(copy))]
+    value: u32,
+}
+
+fn values(tree: &TreeArena<Node>, indices: impl Iterator<Item = Index>) -> Vec<u32> {
+    indices.map(|index| tree.lookup(index).unwrap().value).collect()
+}
+
+// root
+// ├── a
+// │   ├── e
+// │   └── d
+// ├── b
+// └── c
+fn tree() -> (TreeArena<Node>, [Index; 6]) {
+    let mut tree = TreeArena::new();
+    let [root, a, b, c, d, e] = [0, 1, 2, 3, 4, 5].map(|value| tree.add(Node { value }));
+    tree.append(root, a).unwrap();
+    tree.append(root, c).unwrap();
+    tree.insert_before(c, b).unwrap();
+    tree.append(a, d).unwrap();
+    tree.prepend(a, e).unwrap();
+
+    (tree, [root, a, b, c, d, e])
+}
+
+#[test]
+fn traversal_orders() {
+    let (tree, [root, a, _, _, d, _]) = tree();
+    assert_eq!(values(&tree, tree.pre_order(root)), [0, 1, 5, 4, 2, 3]);
+    assert_eq!(values(&tree, tree.post_order(root)), [5, 4, 1, 2, 3, 0]);
+    assert_eq!(values(&tree, tree.breadth_first(root)), [0, 1, 2, 3, 5, 4]);
+    assert_eq!(values(&tree, tree.pre_order(a)), [1, 5, 4]);
+    assert_eq!(values(&tree, tree.ancestors(d)), [1, 0]);
+    assert_eq!(tree.depth(d), 2);
+
+    let handle = tree.handle(a, None);
+    assert_eq!(handle.parent().map(|parent| parent.handle().value()), Some(Some(0)));
+    assert_eq!(
+        handle.children().map(|child| child.handle().value()).collect::<Vec<_>>(),
+        [Some(5), Some(4)]
+    );
+}
+
+#[test]
+fn moves_into_own_subtree_are_rejected() {
+    let (mut tree, [root, a, b, _, d, _]) = tree();
+    assert!(matches!(tree.append(d, root), Err(ArenaError::TreeCycle)));
+    assert!(matches!(tree.prepend(a, a), Err(ArenaError::TreeCycle)));
+    assert!(matches!(tree.insert_after(d, a), Err(ArenaError::TreeCycle)));
+    assert!(matches!(tree.insert_before(root, b), Err(ArenaError::RootSibling)));
+    // Rejected moves leave the tree as it was.
+    assert_eq!(values(&tree, tree.pre_order(root)), [0, 1, 5, 4, 2, 3]);
+
+    // Moving a node elsewhere takes its subtree with it.
+    tree.append(b, a).unwrap();
+    assert_eq!(values(&tree, tree.pre_order(root)), [0, 2, 1, 5, 4, 3]);
+    assert_eq!(tree.depth(d), 3);
+}
+
+#[test]
+fn detach_and_remove_subtrees() {
+    let (mut tree, [root, a, _, c, d, e]) = tree();
+    let removed = tree.remove_subtree(a).unwrap();
+    assert_eq!(removed, [Node { value: 1 }, Node { value: 5 }, Node { value: 4 }]);
+    assert!(!tree.contains(d) && !tree.contains(e));
+    assert_eq!(tree.len(), 3);
+
+    // Removed slots are reused without links left over from the removed nodes.
+    let x = tree.add(Node { value: 9 });
+    assert!(tree.is_root(x) && tree.first_child(x).is_none());
+    tree.append(c, x).unwrap();
+    assert_eq!(values(&tree, tree.pre_order(root)), [0, 2, 3, 9]);
+
+    tree.detach(c).unwrap();
+    assert_eq!(values(&tree, tree.pre_order(root)), [0, 2]);
+    assert_eq!(values(&tree, tree.pre_order(c)), [3, 9]);
+    assert_eq!(tree.roots().count(), 2);
+}
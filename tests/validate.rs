@@ -0,0 +1,47 @@
+use arena_system::{Arena, DanglingKind, Index};
+use arena_system_proc_macro::References;
+
+#[derive(References, Debug)]
+struct Node {
+    #[reference]
+    parent: Index,
+    #[reference]
+    children: Vec<Index>,
+}
+
+fn node(parent: Index, children: Vec<Index>) -> Node {
+    Node { parent, children }
+}
+
+#[test]
+fn reports_dangling_references() {
+    let mut arena = Arena::new();
+    let root = arena.add(node(Index::invalid(), vec![Index::new(1), Index::new(7)]));
+    let removed = arena.add(node(root, vec![]));
+    arena.remove(removed).unwrap();
+
+    let report = arena.validate();
+    let kinds = report
+        .dangling()
+        .iter()
+        .map(|reference| reference.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, [DanglingKind::Invalid, DanglingKind::Removed, DanglingKind::OutOfBounds]);
+    assert!(!report.is_valid());
+}
+
+#[test]
+fn borrowed_elements_are_reported() {
+    let mut arena = Arena::new();
+    let root = arena.add(node(Index::invalid(), vec![]));
+    let child = arena.add(node(root, vec![]));
+    arena.lookup_mut(root).unwrap().parent = child;
+    assert!(arena.validate().is_valid());
+
+    let mut element = arena.lookup_mut(child).unwrap();
+    element.parent = Index::new(9);
+    let report = arena.validate();
+    assert!(report.dangling().is_empty());
+    assert_eq!(report.uninspected(), [child]);
+    assert!(!report.is_valid());
+}
@@ -0,0 +1,68 @@
+use arena_system::{arena_world, ArenaSystem, Handle, Index, World};
+use arena_system_proc_macro::Handleable;
+
+arena_world! {
+    pub struct Scene {
+        nodes: Node,
+        meshes: Mesh,
+    }
+}
+
+#[derive(Handleable)]
+#[handleable(world = Scene)]
+pub struct Node {
+    #[handle_getter(return_type(handle(Mesh)))]
+    mesh: Index,
+    #[handle_getter(return_type(copy))]
+    id: u32,
+}
+
+#[derive(Handleable)]
+#[handleable(world = Scene)]
+pub struct Mesh {
+    #[handle_getter(return_type(copy))]
+    vertices: u32,
+}
+
+#[derive(Handleable)]
+#[handleable(world = ArenaSystem)]
+pub struct Light {
+    #[handle_getter(return_type(handle(Lamp)))]
+    lamp: Index,
+}
+
+#[derive(Handleable)]
+#[handleable(world = ArenaSystem)]
+pub struct Lamp {
+    #[handle_getter(return_type(copy))]
+    power: u32,
+}
+
+#[test]
+fn handle_getters_resolve_against_sibling_arenas() {
+    let mut scene = Scene::new();
+    scene.add(Mesh { vertices: 3 });
+    let mesh = scene.add(Mesh { vertices: 9 });
+    let node = scene.add(Node { mesh, id: 1 });
+    assert_eq!(scene.nodes().len(), 1);
+    assert_eq!(scene.meshes().len(), 2);
+
+    let handle = scene.handle::<Node>(node);
+    assert_eq!(handle.id(), Some(1));
+    assert_eq!(handle.mesh().unwrap().vertices(), Some(9));
+
+    assert_eq!(scene.remove::<Mesh>(mesh).unwrap().vertices, 9);
+    let handle = scene.handle::<Node>(node);
+    assert!(!handle.mesh().unwrap().exists());
+}
+
+#[test]
+fn arena_system_is_a_world() {
+    let mut system = ArenaSystem::new();
+    let lamp = World::add(&mut system, Lamp { power: 60 });
+    let light = World::add(&mut system, Light { lamp });
+
+    let handle = World::handle::<Light>(&system, light);
+    assert_eq!(handle.lamp().unwrap().power(), Some(60));
+    assert_eq!(system.arena::<Light>().handle_iter(&system).filter(|h| h.exists()).count(), 1);
+}